@@ -0,0 +1,61 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use tiny_hderive::bip32::ExtendedPrivKey;
+use tiny_hderive::bip44::ChildNumber;
+use tiny_hderive::tree::KeyTree;
+
+const SEED: &[u8] = &[42; 64];
+
+/// Standard BIP44 gap limit, the number of unused addresses a wallet scans.
+const GAP_LIMIT: u32 = 20;
+
+fn gap_limit_scan(c: &mut Criterion) {
+    let paths: Vec<String> = (0..GAP_LIMIT).map(|i| format!("m/44'/60'/0'/0/{}", i)).collect();
+    let mut group = c.benchmark_group("gap limit scan");
+
+    group.bench_function("derive", |b| {
+        b.iter(|| {
+            for path in paths.iter() {
+                black_box(ExtendedPrivKey::derive(SEED, path.as_str()).unwrap());
+            }
+        })
+    });
+
+    group.bench_function("key tree", |b| {
+        b.iter(|| {
+            let mut tree = KeyTree::new(SEED).unwrap();
+
+            for path in paths.iter() {
+                black_box(tree.derive(path.as_str()).unwrap());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn siblings(c: &mut Criterion) {
+    let account = ExtendedPrivKey::derive(SEED, "m/44'/60'/0'/0").unwrap();
+    let children: Vec<ChildNumber> = (0..10_000).map(ChildNumber::non_hardened_from_u32).collect();
+    let mut group = c.benchmark_group("10k siblings");
+
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(children.len() as u64));
+    group.bench_function("children", |b| b.iter(|| black_box(account.children(&children).unwrap())));
+
+    // A parent parsed from its xprv has yet to compute its public key, and
+    // cloning it copies the empty cache, so every child pays for the scalar
+    // multiplication the cache saves and nothing else.
+    let uncached: ExtendedPrivKey = account.to_string().parse().unwrap();
+
+    group.bench_function("children without cache", |b| {
+        b.iter(|| {
+            for child in children.iter() {
+                black_box(uncached.clone().child(*child).unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, gap_limit_scan, siblings);
+criterion_main!(benches);
@@ -0,0 +1,244 @@
+//! Address encodings for secp256k1 keys.
+//!
+//! Ethereum addresses are available with the `ethereum` feature, Bitcoin
+//! P2PKH, P2SH-P2WPKH and P2WPKH addresses with the `bitcoin` feature.
+
+#[cfg(feature = "bitcoin")]
+use base58::ToBase58;
+#[cfg(feature = "ethereum")]
+use tiny_keccak::{Hasher, Keccak};
+
+#[cfg(feature = "bitcoin")]
+use crate::bip32::{checksum, hash160};
+use crate::bip32::{ExtendedPrivKey, ExtendedPubKey};
+
+/// Network a Bitcoin address is encoded for.
+#[cfg(feature = "bitcoin")]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Network {
+    Bitcoin,
+    Testnet,
+}
+
+#[cfg(feature = "bitcoin")]
+impl Network {
+    fn p2pkh_prefix(self) -> u8 {
+        match self {
+            Network::Bitcoin => 0x00,
+            Network::Testnet => 0x6F,
+        }
+    }
+
+    fn p2sh_prefix(self) -> u8 {
+        match self {
+            Network::Bitcoin => 0x05,
+            Network::Testnet => 0xC4,
+        }
+    }
+
+    fn hrp(self) -> bech32::Hrp {
+        match self {
+            Network::Bitcoin => bech32::hrp::BC,
+            Network::Testnet => bech32::hrp::TB,
+        }
+    }
+}
+
+/// Ethereum address of an uncompressed public key, with EIP-55 checksum casing.
+#[cfg(feature = "ethereum")]
+pub fn ethereum(public_key: &[u8; 65]) -> String {
+    let hash = keccak256(&public_key[1..]);
+
+    eip55(&hash[12..])
+}
+
+/// Legacy pay-to-public-key-hash address of a compressed public key.
+#[cfg(feature = "bitcoin")]
+pub fn p2pkh(public_key: &[u8; 33], network: Network) -> String {
+    base58check(network.p2pkh_prefix(), &hash160(public_key))
+}
+
+/// Pay-to-witness-public-key-hash address nested in pay-to-script-hash,
+/// as used by BIP49 wallets.
+#[cfg(feature = "bitcoin")]
+pub fn p2sh_p2wpkh(public_key: &[u8; 33], network: Network) -> String {
+    let mut script = [0u8; 22];
+
+    script[0] = 0x00;
+    script[1] = 0x14;
+    script[2..].copy_from_slice(&hash160(public_key));
+
+    base58check(network.p2sh_prefix(), &hash160(&script))
+}
+
+/// Native segwit pay-to-witness-public-key-hash address, as used by BIP84
+/// wallets.
+#[cfg(feature = "bitcoin")]
+pub fn p2wpkh(public_key: &[u8; 33], network: Network) -> String {
+    // A 20-byte version 0 program is always valid, so encoding cannot fail.
+    bech32::segwit::encode_v0(network.hrp(), &hash160(public_key)).expect("valid witness program")
+}
+
+macro_rules! address_methods {
+    ($key:ty) => {
+        impl $key {
+            /// Ethereum address of the key, with EIP-55 checksum casing.
+            #[cfg(feature = "ethereum")]
+            pub fn ethereum_address(&self) -> String {
+                ethereum(&self.public_key_uncompressed())
+            }
+
+            /// Legacy pay-to-public-key-hash address of the key.
+            #[cfg(feature = "bitcoin")]
+            pub fn p2pkh_address(&self, network: Network) -> String {
+                p2pkh(&self.public_key(), network)
+            }
+
+            /// Pay-to-witness-public-key-hash address of the key nested in
+            /// pay-to-script-hash.
+            #[cfg(feature = "bitcoin")]
+            pub fn p2sh_p2wpkh_address(&self, network: Network) -> String {
+                p2sh_p2wpkh(&self.public_key(), network)
+            }
+
+            /// Native segwit pay-to-witness-public-key-hash address of the key.
+            #[cfg(feature = "bitcoin")]
+            pub fn p2wpkh_address(&self, network: Network) -> String {
+                p2wpkh(&self.public_key(), network)
+            }
+        }
+    };
+}
+
+address_methods!(ExtendedPrivKey);
+address_methods!(ExtendedPubKey);
+
+#[cfg(feature = "ethereum")]
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut keccak = Keccak::v256();
+    let mut hash = [0u8; 32];
+
+    keccak.update(data);
+    keccak.finalize(&mut hash);
+    hash
+}
+
+/// Hex encodes an address, uppercasing every letter whose nibble in the
+/// Keccak-256 of the lowercase hex is 8 or above.
+#[cfg(feature = "ethereum")]
+fn eip55(address: &[u8]) -> String {
+    let hex: String = address.iter().map(|byte| format!("{:02x}", byte)).collect();
+    let hash = keccak256(hex.as_bytes());
+
+    let checksummed = hex.chars().enumerate().map(|(i, c)| {
+        let nibble = hash[i / 2] >> (4 * (1 - i % 2)) & 0x0F;
+
+        if nibble >= 8 { c.to_ascii_uppercase() } else { c }
+    });
+
+    "0x".chars().chain(checksummed).collect()
+}
+
+#[cfg(feature = "bitcoin")]
+fn base58check(prefix: u8, hash: &[u8; 20]) -> String {
+    let mut data = [0u8; 25];
+
+    data[0] = prefix;
+    data[1..21].copy_from_slice(hash);
+
+    let checksum = checksum(&data[..21]);
+    data[21..].copy_from_slice(&checksum);
+
+    data.to_base58()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bip39::{Mnemonic, Language, Seed};
+
+    fn seed() -> Seed {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let mnemonic = Mnemonic::from_phrase(phrase, Language::English).unwrap();
+
+        Seed::new(&mnemonic, "")
+    }
+
+    #[test]
+    fn public_key_forms() {
+        let key = ExtendedPrivKey::derive(seed().as_bytes(), "m/84'/0'/0'/0/0").unwrap();
+        let compressed = key.public_key();
+        let uncompressed = key.public_key_uncompressed();
+
+        assert_eq!(
+            &compressed[..],
+            &b"\x03\x30\xd5\x4f\xd0\xdd\x42\x0a\x6e\x5f\x8d\x36\x24\xf5\xf3\x48\x2c\xae\x35\x0f\x79\xd5\xf0\x75\x3b\xf5\xbe\xef\x9c\x2d\x91\xaf\x3c"[..]
+        );
+        assert_eq!(uncompressed[0], 0x04);
+        assert_eq!(uncompressed[1..33], compressed[1..]);
+        assert_eq!(uncompressed[64] & 1, compressed[0] & 1);
+        assert_eq!(ExtendedPubKey::from_private(&key).public_key_uncompressed()[..], uncompressed[..]);
+    }
+
+    #[cfg(feature = "ethereum")]
+    #[test]
+    fn eip55_vectors() {
+        let vectors = [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ];
+
+        for address in vectors.iter() {
+            let bytes: Vec<u8> = (2..42).step_by(2).map(|i| u8::from_str_radix(&address[i..i + 2], 16).unwrap()).collect();
+
+            assert_eq!(&eip55(&bytes), address);
+        }
+    }
+
+    #[cfg(feature = "ethereum")]
+    #[test]
+    fn ethereum_address() {
+        let key = ExtendedPrivKey::derive(seed().as_bytes(), "m/44'/60'/0'/0/0").unwrap();
+
+        assert_eq!(key.ethereum_address(), "0x9858EfFD232B4033E47d90003D41EC34EcaEda94");
+        assert_eq!(ExtendedPubKey::from_private(&key).ethereum_address(), "0x9858EfFD232B4033E47d90003D41EC34EcaEda94");
+
+        let phrase = "panda eyebrow bullet gorilla call smoke muffin taste mesh discover soft ostrich alcohol speed nation flash devote level hobby quick inner drive ghost inside";
+        let mnemonic = Mnemonic::from_phrase(phrase, Language::English).unwrap();
+        let seed = Seed::new(&mnemonic, "");
+        let key = ExtendedPrivKey::derive(seed.as_bytes(), "m/44'/60'/0'/0/0").unwrap();
+
+        assert_eq!(key.ethereum_address(), "0x63F9A92D8D61b48a9fFF8d58080425A3012d05C8");
+    }
+
+    #[cfg(feature = "bitcoin")]
+    #[test]
+    fn bitcoin_addresses() {
+        let seed = seed();
+
+        let bip44 = ExtendedPrivKey::derive(seed.as_bytes(), "m/44'/0'/0'/0/0").unwrap();
+        assert_eq!(bip44.p2pkh_address(Network::Bitcoin), "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA");
+        assert_eq!(bip44.p2pkh_address(Network::Testnet), "n1M8ZVQtL7QoFvGMg24D6b2ojWvFXCGpoS");
+
+        let bip49 = ExtendedPrivKey::derive(seed.as_bytes(), "m/49'/0'/0'/0/0").unwrap();
+        assert_eq!(bip49.p2sh_p2wpkh_address(Network::Bitcoin), "37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf");
+        assert_eq!(bip49.p2sh_p2wpkh_address(Network::Testnet), "2My47gHNc8nhX5kBWqXHU4f8uuQvQKEgwMd");
+
+        let bip84 = ExtendedPrivKey::derive(seed.as_bytes(), "m/84'/0'/0'/0/0").unwrap();
+        assert_eq!(bip84.p2wpkh_address(Network::Bitcoin), "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu");
+        assert_eq!(bip84.p2wpkh_address(Network::Testnet), "tb1qcr8te4kr609gcawutmrza0j4xv80jy8zmfp6l0");
+
+        let xpub = ExtendedPubKey::from_private(&bip84);
+        assert_eq!(xpub.p2wpkh_address(Network::Bitcoin), "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu");
+    }
+
+    #[cfg(feature = "bitcoin")]
+    #[test]
+    fn bip49_testnet_vector() {
+        let key = ExtendedPrivKey::derive(seed().as_bytes(), "m/49'/1'/0'/0/0").unwrap();
+
+        assert_eq!(key.p2sh_p2wpkh_address(Network::Testnet), "2Mww8dCYPUpKHofjgcXcBCEGmniw9CoaiD2");
+    }
+}
@@ -1,152 +1,1090 @@
-use secp256k1::{SecretKey, PublicKey};
-use base58::FromBase58;
-use sha2::Sha512;
+use base58::{FromBase58, ToBase58};
+use sha2::{Digest, Sha256, Sha512};
+use ripemd160::Ripemd160;
 use hmac::{Hmac, Mac};
-use memzero::Memzero;
-use std::ops::Deref;
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, Zeroizing};
+use std::convert::TryFrom;
 use std::str::FromStr;
+use std::sync::OnceLock;
 use std::fmt;
 
 use crate::bip44::{ChildNumber, IntoDerivationPath};
+use crate::curve::{Curve, Secp256k1};
+pub use crate::protected::Protected;
+use crate::seed;
 use crate::Error;
 
-#[derive(Clone, PartialEq, Eq)]
-pub struct Protected(Memzero<[u8; 32]>);
+/// SLIP-0132 version bytes of a serialized extended key.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Version {
+    /// `xprv` / `xpub`: mainnet P2PKH or P2SH.
+    #[default]
+    Mainnet,
+    /// `yprv` / `ypub`: mainnet P2WPKH nested in P2SH.
+    MainnetP2shP2wpkh,
+    /// `Yprv` / `Ypub`: mainnet multi-signature P2WSH nested in P2SH.
+    MainnetP2shP2wsh,
+    /// `zprv` / `zpub`: mainnet P2WPKH.
+    MainnetP2wpkh,
+    /// `Zprv` / `Zpub`: mainnet multi-signature P2WSH.
+    MainnetP2wsh,
+    /// `tprv` / `tpub`: testnet P2PKH or P2SH.
+    Testnet,
+    /// `uprv` / `upub`: testnet P2WPKH nested in P2SH.
+    TestnetP2shP2wpkh,
+    /// `Uprv` / `Upub`: testnet multi-signature P2WSH nested in P2SH.
+    TestnetP2shP2wsh,
+    /// `vprv` / `vpub`: testnet P2WPKH.
+    TestnetP2wpkh,
+    /// `Vprv` / `Vpub`: testnet multi-signature P2WSH.
+    TestnetP2wsh,
+    /// Version bytes not registered in SLIP-0132. These are never
+    /// recognized by `FromStr`, see `ExtendedPrivKey::from_str_with_version`.
+    Custom {
+        private: [u8; 4],
+        public: [u8; 4],
+    },
+}
 
-impl<Data: AsRef<[u8]>> From<Data> for Protected {
-    fn from(data: Data) -> Protected {
-        let mut buf = [0u8; 32];
+/// All versions recognized when parsing.
+const REGISTERED_VERSIONS: [Version; 10] = [
+    Version::Mainnet,
+    Version::MainnetP2shP2wpkh,
+    Version::MainnetP2shP2wsh,
+    Version::MainnetP2wpkh,
+    Version::MainnetP2wsh,
+    Version::Testnet,
+    Version::TestnetP2shP2wpkh,
+    Version::TestnetP2shP2wsh,
+    Version::TestnetP2wpkh,
+    Version::TestnetP2wsh,
+];
 
-        buf.copy_from_slice(data.as_ref());
+impl Version {
+    /// Version bytes of a serialized private key.
+    pub fn private(&self) -> [u8; 4] {
+        match *self {
+            Version::Mainnet => [0x04, 0x88, 0xAD, 0xE4],
+            Version::MainnetP2shP2wpkh => [0x04, 0x9D, 0x78, 0x78],
+            Version::MainnetP2shP2wsh => [0x02, 0x95, 0xB0, 0x05],
+            Version::MainnetP2wpkh => [0x04, 0xB2, 0x43, 0x0C],
+            Version::MainnetP2wsh => [0x02, 0xAA, 0x7A, 0x99],
+            Version::Testnet => [0x04, 0x35, 0x83, 0x94],
+            Version::TestnetP2shP2wpkh => [0x04, 0x4A, 0x4E, 0x28],
+            Version::TestnetP2shP2wsh => [0x02, 0x42, 0x85, 0xB5],
+            Version::TestnetP2wpkh => [0x04, 0x5F, 0x18, 0xBC],
+            Version::TestnetP2wsh => [0x02, 0x57, 0x50, 0x48],
+            Version::Custom { private, .. } => private,
+        }
+    }
 
-        Protected(Memzero::from(buf))
+    /// Version bytes of a serialized public key.
+    pub fn public(&self) -> [u8; 4] {
+        match *self {
+            Version::Mainnet => [0x04, 0x88, 0xB2, 0x1E],
+            Version::MainnetP2shP2wpkh => [0x04, 0x9D, 0x7C, 0xB2],
+            Version::MainnetP2shP2wsh => [0x02, 0x95, 0xB4, 0x3F],
+            Version::MainnetP2wpkh => [0x04, 0xB2, 0x47, 0x46],
+            Version::MainnetP2wsh => [0x02, 0xAA, 0x7E, 0xD3],
+            Version::Testnet => [0x04, 0x35, 0x87, 0xCF],
+            Version::TestnetP2shP2wpkh => [0x04, 0x4A, 0x52, 0x62],
+            Version::TestnetP2shP2wsh => [0x02, 0x42, 0x89, 0xEF],
+            Version::TestnetP2wpkh => [0x04, 0x5F, 0x1C, 0xF6],
+            Version::TestnetP2wsh => [0x02, 0x57, 0x54, 0x83],
+            Version::Custom { public, .. } => public,
+        }
     }
-}
 
-impl Deref for Protected {
-    type Target = [u8];
+    fn from_private(bytes: &[u8]) -> Option<Version> {
+        REGISTERED_VERSIONS.iter().cloned().find(|version| version.private() == bytes)
+    }
 
-    fn deref(&self) -> &[u8] {
-        self.0.as_ref()
+    fn from_public(bytes: &[u8]) -> Option<Version> {
+        REGISTERED_VERSIONS.iter().cloned().find(|version| version.public() == bytes)
     }
 }
 
-impl fmt::Debug for Protected {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Protected")
-    }
+/// How to handle a child key that is invalid because IL is not below the
+/// curve order or the resulting key is zero. The odds of hitting this are
+/// lower than 1 in 2^127.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum InvalidKeyPolicy {
+    /// Fail with `Error::InvalidChildKey`.
+    Error,
+    /// Proceed with the next child index, as BIP32 specifies. The index
+    /// that was used is reported by `child_number`.
+    NextIndex,
+    /// Retry with the HMAC of `0x01 || IR || ser32(i)`, as SLIP-0010
+    /// specifies. A master key is rehashed with the HMAC of `I`.
+    Rehash,
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
-pub struct ExtendedPrivKey {
-    secret_key: SecretKey,
-    chain_code: Protected,
+/// Extended private key on the curve `C`. See `ExtendedPrivKey` for the
+/// secp256k1 key used by Bitcoin and Ethereum.
+#[derive(Clone)]
+pub struct GenericExtendedPrivKey<C: Curve> {
+    secret_key: C::SecretKey,
+    pub(crate) chain_code: Protected,
+    version: Version,
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: ChildNumber,
+    /// Compressed public key, computed on first use so that sibling
+    /// derivations share a single scalar multiplication.
+    public_key: OnceLock<[u8; 33]>,
 }
 
-impl ExtendedPrivKey {
-    /// Attempts to derive an extended private key from a path.
-    pub fn derive<Path>(seed: &[u8], path: Path) -> Result<ExtendedPrivKey, Error>
+/// BIP32 extended private key on the secp256k1 curve.
+pub type ExtendedPrivKey = GenericExtendedPrivKey<Secp256k1>;
+
+impl<C: Curve> GenericExtendedPrivKey<C> {
+    /// Attempts to derive an extended private key from a path. Relative
+    /// paths are applied to the master key, and paths starting from the
+    /// master public key `M` fail with `Error::PublicPathRoot`.
+    pub fn derive<Path>(seed: &[u8], path: Path) -> Result<GenericExtendedPrivKey<C>, Error>
     where
         Path: IntoDerivationPath,
     {
-        let mut hmac: Hmac<Sha512> = Hmac::new_varkey(b"Bitcoin seed").expect("seed is always correct; qed");
+        GenericExtendedPrivKey::derive_with_policy(seed, path, C::INVALID_KEY_POLICY)
+    }
+
+    /// Attempts to derive an extended private key from a path, handling
+    /// invalid keys according to `policy`. BIP32 considers a seed yielding
+    /// an invalid master key to be invalid, so `NextIndex` fails on it.
+    ///
+    /// Fails with `Error::InvalidSeedLength` unless the seed is 128 to 512
+    /// bits long, see `Seed`.
+    pub fn derive_with_policy<Path>(
+        seed: &[u8],
+        path: Path,
+        policy: InvalidKeyPolicy,
+    ) -> Result<GenericExtendedPrivKey<C>, Error>
+    where
+        Path: IntoDerivationPath,
+    {
+        seed::check_length(seed)?;
+
+        let path = path.into()?;
+        path.check_private()?;
+
+        let mut hmac: Hmac<Sha512> = Hmac::new_varkey(C::SEED_KEY).expect("seed is always correct; qed");
         hmac.input(seed);
 
-        let result = hmac.result().code();
-        let (secret_key, chain_code) = result.split_at(32);
+        let mut result = hmac_result(hmac);
 
-        let mut sk = ExtendedPrivKey {
-            secret_key: SecretKey::parse_slice(secret_key).map_err(Error::Secp256k1)?,
-            chain_code: Protected::from(chain_code),
+        let mut sk = loop {
+            let (secret_key, chain_code) = result.halves();
+
+            match C::parse_secret(secret_key) {
+                Ok(secret_key) => break GenericExtendedPrivKey {
+                    secret_key,
+                    chain_code: Protected::from(chain_code),
+                    version: Version::default(),
+                    depth: 0,
+                    parent_fingerprint: [0; 4],
+                    child_number: ChildNumber::non_hardened_from_u32(0),
+                    public_key: OnceLock::new(),
+                },
+                Err(_) if policy == InvalidKeyPolicy::Rehash => {
+                    let mut hmac: Hmac<Sha512> = Hmac::new_varkey(C::SEED_KEY).expect("seed is always correct; qed");
+                    hmac.input(&result[..]);
+
+                    result = hmac_result(hmac);
+                }
+                Err(err) => return Err(err),
+            }
         };
 
-        for child in path.into()?.as_ref() {
-            sk = sk.child(*child)?;
+        for child in path.as_ref() {
+            sk = sk.child_with_policy(*child, policy)?;
         }
 
         Ok(sk)
     }
 
-    pub fn secret(&self) -> [u8; 32] {
-        self.secret_key.serialize()
+    /// The secret key, wiped from memory when the returned buffer is dropped.
+    pub fn secret(&self) -> Protected {
+        C::serialize_secret(&self.secret_key)
+    }
+
+    /// Overwrites the secret key, as done when the key is dropped. The
+    /// chain code is wiped by `Protected` itself.
+    pub(crate) fn erase(&mut self) {
+        C::erase_secret(&mut self.secret_key);
+    }
+
+    /// Compressed SEC1 encoding of the public key.
+    pub fn public_key(&self) -> [u8; 33] {
+        *self.public_key.get_or_init(|| C::serialize_public(&C::public_key(&self.secret_key)))
+    }
+
+    /// Uncompressed SEC1 encoding of the public key.
+    pub fn public_key_uncompressed(&self) -> [u8; 65] {
+        C::serialize_public_uncompressed(&C::public_key(&self.secret_key))
+    }
+
+    /// Version the key was parsed with, `Version::Mainnet` for derived keys.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Number of derivation steps from the master key.
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Fingerprint of the parent key, zeroed for the master key.
+    pub fn parent_fingerprint(&self) -> [u8; 4] {
+        self.parent_fingerprint
+    }
+
+    /// Child number this key was derived with, zero for the master key.
+    pub fn child_number(&self) -> ChildNumber {
+        self.child_number
+    }
+
+    /// Fingerprint of this key, the first four bytes of the HASH160 of
+    /// its compressed public key.
+    pub fn fingerprint(&self) -> [u8; 4] {
+        fingerprint(&self.public_key())
     }
 
-    pub fn child(&self, child: ChildNumber) -> Result<ExtendedPrivKey, Error> {
-        let mut hmac: Hmac<Sha512> = Hmac::new_varkey(&self.chain_code)
-            .map_err(|_| Error::InvalidChildNumber)?;
+    pub fn child(&self, child: ChildNumber) -> Result<GenericExtendedPrivKey<C>, Error> {
+        self.child_with_policy(child, C::INVALID_KEY_POLICY)
+    }
+
+    /// Derives a child for each of `children`, in order, failing with the
+    /// first error.
+    pub fn children(&self, children: &[ChildNumber]) -> Result<Vec<GenericExtendedPrivKey<C>>, Error> {
+        children.iter().map(|child| self.child(*child)).collect()
+    }
 
-        if child.is_normal() {
-            hmac.input(&PublicKey::from_secret_key(&self.secret_key).serialize_compressed()[..]);
+    /// Attempts to derive a child key, handling an invalid key according
+    /// to `policy`.
+    pub fn child_with_policy(
+        &self,
+        child: ChildNumber,
+        policy: InvalidKeyPolicy,
+    ) -> Result<GenericExtendedPrivKey<C>, Error> {
+        let depth = self.depth.checked_add(1).ok_or(Error::MaxDepthExceeded)?;
+        let public_key = self.public_key();
+        let tweak = |tweak: &[u8]| C::tweak_add(&self.secret_key, tweak);
+
+        let (secret_key, chain_code, child_number) = if child.is_normal() {
+            ckd(&self.chain_code, &public_key, child, policy, tweak)?
         } else {
-            hmac.input(&[0]);
-            hmac.input(&self.secret_key.serialize()[..]);
+            let mut data = Protected::<33>::default();
+            data[1..].copy_from_slice(&*self.secret());
+
+            ckd(&self.chain_code, &data[..], child, policy, tweak)?
+        };
+
+        Ok(GenericExtendedPrivKey {
+            secret_key,
+            chain_code,
+            version: self.version,
+            depth,
+            parent_fingerprint: fingerprint(&public_key),
+            child_number,
+            public_key: OnceLock::new(),
+        })
+    }
+}
+
+impl ExtendedPrivKey {
+    /// Parses a key serialized with exactly the given version, which may be
+    /// a `Version::Custom` pair unknown to `FromStr`.
+    pub fn from_str_with_version(xprv: &str, version: Version) -> Result<ExtendedPrivKey, Error> {
+        let data = decode(xprv, Error::InvalidExtendedPrivKey)?;
+
+        if data[0..4] != version.private() {
+            return Err(Error::UnknownVersion);
         }
 
-        hmac.input(&child.to_bytes());
+        ExtendedPrivKey::from_payload(&data, version)
+    }
+
+    /// Serializes the key with the given version bytes instead of its own.
+    pub fn to_string_with_version(&self, version: Version) -> String {
+        let mut key = Protected::<33>::default();
+
+        key[1..].copy_from_slice(&*self.secret());
+
+        encode(
+            version.private(),
+            self.depth,
+            &self.parent_fingerprint,
+            self.child_number,
+            &self.chain_code,
+            &key[..],
+        )
+    }
 
-        let result = hmac.result().code();
-        let (secret_key, chain_code) = result.split_at(32);
+    fn from_payload(data: &[u8; 82], version: Version) -> Result<ExtendedPrivKey, Error> {
+        if data[45] != 0 {
+            return Err(Error::InvalidPrivateKeyPadding);
+        }
 
-        let mut secret_key = SecretKey::parse_slice(&secret_key).map_err(Error::Secp256k1)?;
-        secret_key.tweak_add_assign(&self.secret_key).map_err(Error::Secp256k1)?;
+        let (parent_fingerprint, child_number) = origin(data);
 
         Ok(ExtendedPrivKey {
-            secret_key,
-            chain_code: Protected::from(&chain_code)
+            secret_key: Secp256k1::parse_secret(&data[46..78])?,
+            chain_code: Protected::try_from(&data[13..45])?,
+            version,
+            depth: data[4],
+            parent_fingerprint,
+            child_number,
+            public_key: OnceLock::new(),
         })
     }
 }
 
+/// Compares the secret key and chain code in constant time. The cached
+/// public key follows from the secret key and is ignored.
+impl<C: Curve> PartialEq for GenericExtendedPrivKey<C> {
+    fn eq(&self, other: &GenericExtendedPrivKey<C>) -> bool {
+        let keys = self.secret().ct_eq(&*other.secret()) & self.chain_code.ct_eq(&*other.chain_code);
+
+        bool::from(keys)
+            && self.version == other.version
+            && self.depth == other.depth
+            && self.parent_fingerprint == other.parent_fingerprint
+            && self.child_number == other.child_number
+    }
+}
+
+impl<C: Curve> Eq for GenericExtendedPrivKey<C> {}
+
+/// Shows only public metadata, never the secret key or chain code.
+impl<C: Curve> fmt::Debug for GenericExtendedPrivKey<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GenericExtendedPrivKey")
+            .field("curve", &format_args!("{}", C::NAME))
+            .field("version", &self.version)
+            .field("depth", &self.depth)
+            .field("parent_fingerprint", &format_args!("{}", hex(&self.parent_fingerprint)))
+            .field("child_number", &format_args!("{}", self.child_number))
+            .field("fingerprint", &format_args!("{}", hex(&self.fingerprint())))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Curve> Drop for GenericExtendedPrivKey<C> {
+    fn drop(&mut self) {
+        self.erase();
+    }
+}
+
+/// Extended public key on the curve `C`. See `ExtendedPubKey` for the
+/// secp256k1 key used by Bitcoin and Ethereum.
+#[derive(Clone)]
+pub struct GenericExtendedPubKey<C: Curve> {
+    public_key: C::PublicKey,
+    chain_code: Protected,
+    version: Version,
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: ChildNumber,
+}
+
+/// BIP32 extended public key on the secp256k1 curve.
+pub type ExtendedPubKey = GenericExtendedPubKey<Secp256k1>;
+
+impl<C: Curve> GenericExtendedPubKey<C> {
+    /// Creates the extended public key matching an extended private key.
+    ///
+    /// Reuses the private key's cached public key when it has one, since
+    /// parsing a compressed point is several times cheaper than a scalar
+    /// multiplication. Otherwise the computed key fills the cache.
+    pub fn from_private(sk: &GenericExtendedPrivKey<C>) -> GenericExtendedPubKey<C> {
+        let public_key = match sk.public_key.get() {
+            Some(serialized) => C::parse_public(serialized).expect("cached public key was serialized by the backend; qed"),
+            None => {
+                let public_key = C::public_key(&sk.secret_key);
+                let _ = sk.public_key.set(C::serialize_public(&public_key));
+
+                public_key
+            }
+        };
+
+        GenericExtendedPubKey {
+            public_key,
+            chain_code: sk.chain_code.clone(),
+            version: sk.version,
+            depth: sk.depth,
+            parent_fingerprint: sk.parent_fingerprint,
+            child_number: sk.child_number,
+        }
+    }
+
+    /// Compressed SEC1 encoding of the public key.
+    pub fn public_key(&self) -> [u8; 33] {
+        C::serialize_public(&self.public_key)
+    }
+
+    /// Uncompressed SEC1 encoding of the public key.
+    pub fn public_key_uncompressed(&self) -> [u8; 65] {
+        C::serialize_public_uncompressed(&self.public_key)
+    }
+
+    /// Version the key was parsed with, `Version::Mainnet` for derived keys.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Number of derivation steps from the master key.
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Fingerprint of the parent key, zeroed for the master key.
+    pub fn parent_fingerprint(&self) -> [u8; 4] {
+        self.parent_fingerprint
+    }
+
+    /// Child number this key was derived with, zero for the master key.
+    pub fn child_number(&self) -> ChildNumber {
+        self.child_number
+    }
+
+    /// Fingerprint of this key, the first four bytes of the HASH160 of
+    /// its compressed public key.
+    pub fn fingerprint(&self) -> [u8; 4] {
+        fingerprint(&self.public_key())
+    }
+
+    /// Attempts to derive a normal child key. Hardened children can only
+    /// be derived from an extended private key.
+    pub fn child(&self, child: ChildNumber) -> Result<GenericExtendedPubKey<C>, Error> {
+        self.child_with_policy(child, C::INVALID_KEY_POLICY)
+    }
+
+    /// Derives a child for each of `children`, in order, failing with the
+    /// first error.
+    pub fn children(&self, children: &[ChildNumber]) -> Result<Vec<GenericExtendedPubKey<C>>, Error> {
+        children.iter().map(|child| self.child(*child)).collect()
+    }
+
+    /// Attempts to derive a normal child key, handling an invalid key
+    /// according to `policy`.
+    pub fn child_with_policy(
+        &self,
+        child: ChildNumber,
+        policy: InvalidKeyPolicy,
+    ) -> Result<GenericExtendedPubKey<C>, Error> {
+        if child.is_hardened() {
+            return Err(Error::HardenedPublicDerivation);
+        }
+
+        let depth = self.depth.checked_add(1).ok_or(Error::MaxDepthExceeded)?;
+        let parent_key = self.public_key();
+
+        let (public_key, chain_code, child_number) = ckd(&self.chain_code, &parent_key, child, policy, |tweak| {
+            C::point_add(&self.public_key, tweak)
+        })?;
+
+        Ok(GenericExtendedPubKey {
+            public_key,
+            chain_code,
+            version: self.version,
+            depth,
+            parent_fingerprint: fingerprint(&parent_key),
+            child_number,
+        })
+    }
+}
+
+impl ExtendedPubKey {
+    /// Parses a key serialized with exactly the given version, which may be
+    /// a `Version::Custom` pair unknown to `FromStr`.
+    pub fn from_str_with_version(xpub: &str, version: Version) -> Result<ExtendedPubKey, Error> {
+        let data = decode(xpub, Error::InvalidExtendedPubKey)?;
+
+        if data[0..4] != version.public() {
+            return Err(Error::UnknownVersion);
+        }
+
+        ExtendedPubKey::from_payload(&data, version)
+    }
+
+    /// Serializes the key with the given version bytes instead of its own.
+    pub fn to_string_with_version(&self, version: Version) -> String {
+        encode(
+            version.public(),
+            self.depth,
+            &self.parent_fingerprint,
+            self.child_number,
+            &self.chain_code,
+            &self.public_key(),
+        )
+    }
+
+    fn from_payload(data: &[u8; 82], version: Version) -> Result<ExtendedPubKey, Error> {
+        let (parent_fingerprint, child_number) = origin(data);
+
+        Ok(ExtendedPubKey {
+            public_key: Secp256k1::parse_public(&data[45..78])?,
+            chain_code: Protected::try_from(&data[13..45])?,
+            version,
+            depth: data[4],
+            parent_fingerprint,
+            child_number,
+        })
+    }
+}
+
+// Curve backends don't all compare points by value, so keys are compared by
+// their serialized form instead.
+impl<C: Curve> PartialEq for GenericExtendedPubKey<C> {
+    fn eq(&self, other: &GenericExtendedPubKey<C>) -> bool {
+        self.public_key()[..] == other.public_key()[..]
+            && self.chain_code == other.chain_code
+            && self.version == other.version
+            && self.depth == other.depth
+            && self.parent_fingerprint == other.parent_fingerprint
+            && self.child_number == other.child_number
+    }
+}
+
+impl<C: Curve> Eq for GenericExtendedPubKey<C> {}
+
+/// Shows the serialized public key and metadata rather than the backend's
+/// point representation. The chain code is left out.
+impl<C: Curve> fmt::Debug for GenericExtendedPubKey<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GenericExtendedPubKey")
+            .field("curve", &format_args!("{}", C::NAME))
+            .field("version", &self.version)
+            .field("depth", &self.depth)
+            .field("parent_fingerprint", &format_args!("{}", hex(&self.parent_fingerprint)))
+            .field("child_number", &format_args!("{}", self.child_number))
+            .field("public_key", &format_args!("{}", hex(&self.public_key())))
+            .field("fingerprint", &format_args!("{}", hex(&self.fingerprint())))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Child key derivation shared by private and public keys: computes the
+/// HMAC of `data || ser32(child)` and hands IL to `tweak`. Returns the key,
+/// its chain code and the child number that was actually used.
+fn ckd<Key>(
+    chain_code: &[u8; 32],
+    data: &[u8],
+    mut child: ChildNumber,
+    policy: InvalidKeyPolicy,
+    mut tweak: impl FnMut(&[u8]) -> Result<Key, Error>,
+) -> Result<(Key, Protected, ChildNumber), Error> {
+    let mut result = ckd_hmac(chain_code, &[data], child)?;
+
+    loop {
+        let (il, ir) = result.halves();
+
+        result = match (tweak(il), policy) {
+            (Ok(key), _) => return Ok((key, Protected::from(ir), child)),
+            (Err(err), InvalidKeyPolicy::Error) => return Err(err),
+            (Err(_), InvalidKeyPolicy::NextIndex) => {
+                child = child.increment().ok_or(Error::InvalidChildNumber)?;
+
+                ckd_hmac(chain_code, &[data], child)?
+            }
+            (Err(_), InvalidKeyPolicy::Rehash) => ckd_hmac(chain_code, &[&[1], &ir[..]], child)?,
+        };
+    }
+}
+
+fn ckd_hmac(chain_code: &[u8; 32], data: &[&[u8]], child: ChildNumber) -> Result<Protected<64>, Error> {
+    let mut hmac: Hmac<Sha512> = Hmac::new_varkey(chain_code)
+        .map_err(|_| Error::InvalidChildNumber)?;
+
+    for data in data {
+        hmac.input(data);
+    }
+
+    hmac.input(&child.to_bytes());
+
+    Ok(hmac_result(hmac))
+}
+
+/// Moves the HMAC output into a buffer that is wiped on drop.
+pub(crate) fn hmac_result(hmac: Hmac<Sha512>) -> Protected<64> {
+    let mut code = hmac.result().code();
+    let mut result = Protected::<64>::default();
+
+    result.copy_from_slice(&code);
+    code[..].zeroize();
+
+    result
+}
+
 impl FromStr for ExtendedPrivKey {
     type Err = Error;
 
     fn from_str(xprv: &str) -> Result<ExtendedPrivKey, Error> {
-        let data = xprv.from_base58().map_err(|_| Error::InvalidExtendedPrivKey)?;
+        let data = decode(xprv, Error::InvalidExtendedPrivKey)?;
+        let version = Version::from_private(&data[0..4]).ok_or(Error::UnknownVersion)?;
 
-        if data.len() != 82 {
-            return Err(Error::InvalidExtendedPrivKey);
-        }
+        ExtendedPrivKey::from_payload(&data, version)
+    }
+}
 
-        Ok(ExtendedPrivKey {
-            chain_code: Protected::from(&data[13..45]),
-            secret_key: SecretKey::parse_slice(&data[46..78]).map_err(|e| Error::Secp256k1(e))?
-        })
+impl fmt::Display for ExtendedPrivKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_string_with_version(self.version))
+    }
+}
+
+impl FromStr for ExtendedPubKey {
+    type Err = Error;
+
+    fn from_str(xpub: &str) -> Result<ExtendedPubKey, Error> {
+        let data = decode(xpub, Error::InvalidExtendedPubKey)?;
+        let version = Version::from_public(&data[0..4]).ok_or(Error::UnknownVersion)?;
+
+        ExtendedPubKey::from_payload(&data, version)
+    }
+}
+
+impl fmt::Display for ExtendedPubKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_string_with_version(self.version))
+    }
+}
+
+/// Decodes a Base58Check extended key, checking its length, checksum and
+/// the fields that must be zero for a master key.
+fn decode(encoded: &str, invalid: Error) -> Result<Protected<82>, Error> {
+    let decoded = Zeroizing::new(encoded.from_base58().map_err(|_| invalid.clone())?);
+    let data = Protected::<82>::try_from(&decoded[..]).map_err(|_| invalid)?;
+
+    if checksum(&data[..78]) != data[78..82] {
+        return Err(Error::InvalidChecksum);
+    }
+
+    if data[4] == 0 && data[5..9] != [0; 4] {
+        return Err(Error::ZeroDepthParentFingerprint);
+    }
+
+    if data[4] == 0 && data[9..13] != [0; 4] {
+        return Err(Error::ZeroDepthChildNumber);
     }
+
+    Ok(data)
+}
+
+/// Encodes the 78-byte BIP32 payload, with `key` holding either a compressed
+/// public key or a private key prefixed with a zero byte.
+fn encode(
+    version: [u8; 4],
+    depth: u8,
+    parent_fingerprint: &[u8; 4],
+    child_number: ChildNumber,
+    chain_code: &[u8; 32],
+    key: &[u8],
+) -> String {
+    let mut data = Protected::<82>::default();
+
+    data[0..4].copy_from_slice(&version);
+    data[4] = depth;
+    data[5..9].copy_from_slice(parent_fingerprint);
+    data[9..13].copy_from_slice(&child_number.to_bytes());
+    data[13..45].copy_from_slice(chain_code);
+    data[45..78].copy_from_slice(key);
+
+    let checksum = checksum(&data[..78]);
+    data[78..82].copy_from_slice(&checksum);
+
+    data[..].to_base58()
+}
+
+/// Parent fingerprint and child number of a decoded payload.
+fn origin(data: &[u8]) -> ([u8; 4], ChildNumber) {
+    let mut parent_fingerprint = [0u8; 4];
+    let mut child_number = [0u8; 4];
+
+    parent_fingerprint.copy_from_slice(&data[5..9]);
+    child_number.copy_from_slice(&data[9..13]);
+
+    (parent_fingerprint, ChildNumber::from(u32::from_be_bytes(child_number)))
+}
+
+/// First four bytes of the HASH160 of a compressed public key.
+pub(crate) fn fingerprint(public_key: &[u8]) -> [u8; 4] {
+    let hash = hash160(public_key);
+    let mut fingerprint = [0u8; 4];
+
+    fingerprint.copy_from_slice(&hash[..4]);
+    fingerprint
+}
+
+/// RIPEMD160 of the SHA256 of the data.
+pub(crate) fn hash160(data: &[u8]) -> [u8; 20] {
+    let hash = Ripemd160::digest(&Sha256::digest(data));
+    let mut hash160 = [0u8; 20];
+
+    hash160.copy_from_slice(&hash);
+    hash160
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// First four bytes of the double SHA256 of the payload, as used by Base58Check.
+pub(crate) fn checksum(data: &[u8]) -> [u8; 4] {
+    let hash = Sha256::digest(&Sha256::digest(data));
+    let mut checksum = [0u8; 4];
+
+    checksum.copy_from_slice(&hash[..4]);
+    checksum
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use bip39::{Mnemonic, Language, Seed};
-    use ethsign::SecretKey;
 
     #[test]
-    fn bip39_to_address() {
+    fn bip39_to_secret() {
         let phrase = "panda eyebrow bullet gorilla call smoke muffin taste mesh discover soft ostrich alcohol speed nation flash devote level hobby quick inner drive ghost inside";
 
         let expected_secret_key = b"\xff\x1e\x68\xeb\x7b\xf2\xf4\x86\x51\xc4\x7e\xf0\x17\x7e\xb8\x15\x85\x73\x22\x25\x7c\x58\x94\xbb\x4c\xfd\x11\x76\xc9\x98\x93\x14";
-        let expected_address: &[u8] = b"\x63\xF9\xA9\x2D\x8D\x61\xb4\x8a\x9f\xFF\x8d\x58\x08\x04\x25\xA3\x01\x2d\x05\xC8";
 
         let mnemonic = Mnemonic::from_phrase(phrase, Language::English).unwrap();
         let seed = Seed::new(&mnemonic, "");
 
         let account = ExtendedPrivKey::derive(seed.as_bytes(), "m/44'/60'/0'/0/0").unwrap();
 
-        assert_eq!(expected_secret_key, &account.secret(), "Secret key is invalid");
-
-        let secret_key = SecretKey::from_raw(&account.secret()).unwrap();
-        let public_key = secret_key.public();
-
-        assert_eq!(expected_address, public_key.address(), "Address is invalid");
+        assert_eq!(expected_secret_key, &*account.secret(), "Secret key is invalid");
 
         // Test child method
         let account = ExtendedPrivKey::derive(seed.as_bytes(), "m/44'/60'/0'/0").unwrap().child(ChildNumber::from_str("0").unwrap()).unwrap();
 
-        assert_eq!(expected_secret_key, &account.secret(), "Secret key is invalid");
+        assert_eq!(expected_secret_key, &*account.secret(), "Secret key is invalid");
+    }
+
+    #[test]
+    fn xprv_round_trip() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+        let vectors = [
+            ("m", "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi"),
+            ("m/0'", "xprv9uHRZZhk6KAJC1avXpDAp4MDc3sQKNxDiPvvkX8Br5ngLNv1TxvUxt4cV1rGL5hj6KCesnDYUhd7oWgT11eZG7XnxHrnYeSvkzY7d2bhkJ7"),
+            ("m/0'/1", "xprv9wTYmMFdV23N2TdNG573QoEsfRrWKQgWeibmLntzniatZvR9BmLnvSxqu53Kw1UmYPxLgboyZQaXwTCg8MSY3H2EU4pWcQDnRnrVA1xe8fs"),
+            ("m/0'/1/2'", "xprv9z4pot5VBttmtdRTWfWQmoH1taj2axGVzFqSb8C9xaxKymcFzXBDptWmT7FwuEzG3ryjH4ktypQSAewRiNMjANTtpgP4mLTj34bhnZX7UiM"),
+            ("m/0'/1/2'/2", "xprvA2JDeKCSNNZky6uBCviVfJSKyQ1mDYahRjijr5idH2WwLsEd4Hsb2Tyh8RfQMuPh7f7RtyzTtdrbdqqsunu5Mm3wDvUAKRHSC34sJ7in334"),
+            ("m/0'/1/2'/2/1000000000", "xprvA41z7zogVVwxVSgdKUHDy1SKmdb533PjDz7J6N6mV6uS3ze1ai8FHa8kmHScGpWmj4WggLyQjgPie1rFSruoUihUZREPSL39UNdE3BBDu76"),
+        ];
+
+        for (path, xprv) in vectors.iter() {
+            let sk = ExtendedPrivKey::derive(seed, *path).unwrap();
+
+            assert_eq!(&sk.to_string(), xprv, "Serialization of {} is invalid", path);
+            assert_eq!(ExtendedPrivKey::from_str(xprv).unwrap(), sk, "Parsing of {} is invalid", path);
+        }
+    }
+
+    #[test]
+    fn xpub_round_trip() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+        let vectors = [
+            ("m", "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"),
+            ("m/0'", "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw"),
+            ("m/0'/1/2'/2/1000000000", "xpub6H1LXWLaKsWFhvm6RVpEL9P4KfRZSW7abD2ttkWP3SSQvnyA8FSVqNTEcYFgJS2UaFcxupHiYkro49S8yGasTvXEYBVPamhGW6cFJodrTHy"),
+        ];
+
+        for (path, xpub) in vectors.iter() {
+            let pk = ExtendedPubKey::from_private(&ExtendedPrivKey::derive(seed, *path).unwrap());
+
+            assert_eq!(&pk.to_string(), xpub, "Serialization of {} is invalid", path);
+            assert_eq!(ExtendedPubKey::from_str(xpub).unwrap(), pk, "Parsing of {} is invalid", path);
+        }
+    }
+
+    #[test]
+    fn slip132_versions() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+        let sk = ExtendedPrivKey::derive(seed, "m/0'/1").unwrap();
+        let pk = ExtendedPubKey::from_private(&sk);
+
+        let tprv = "tprv8e8VYgZxtHsSdGrtvdxYaSrryZGiYviWzGWtDDKTGh5NMXAEB8gYSCLHpFCywNs5uqV7ghRjimALQJkRFZnUrLHpzi2pGkwqLtbubgWuQ8q";
+        let tpub = "tpubDApXh6cD2fZ7WjtgpHd8yrWyYaneiFuRZa7fVjMkgxsmC1QzoXW8cgx9zQFJ81Jx4deRGfRE7yXA9A3STsxXj4CKEZJHYgpMYikkas9DBTP";
+        let zprv = "zprvAb85NgbTnP8Kj41bvngHpyRt1N9QCefWUweCuagmYjLeg83bh5fvAaH7wUxVvpncMgBxBZ16UjHdi2RoZkGZdkPSCkDMnDrkyEymwBC4DQJ";
+        let zpub = "zpub6p7RnC8MckgcwY652pDJC7NcZPytc7PMrAZohy6P74sdYvNkEczAiNbbnn5gbKfZ61M8A36UWCQDDYmxWQwKS67Dudwq2yo6WDHdc193BuK";
+
+        assert_eq!(sk.to_string_with_version(Version::Testnet), tprv);
+        assert_eq!(pk.to_string_with_version(Version::Testnet), tpub);
+        assert_eq!(sk.to_string_with_version(Version::MainnetP2wpkh), zprv);
+        assert_eq!(pk.to_string_with_version(Version::MainnetP2wpkh), zpub);
+
+        let parsed = ExtendedPrivKey::from_str(tprv).unwrap();
+        assert_eq!(parsed.version(), Version::Testnet);
+        assert_eq!(parsed.secret(), sk.secret());
+        assert_eq!(parsed.to_string(), tprv);
+
+        let parsed = ExtendedPubKey::from_str(zpub).unwrap();
+        assert_eq!(parsed.version(), Version::MainnetP2wpkh);
+        assert_eq!(parsed.public_key(), pk.public_key());
+        assert_eq!(parsed.to_string(), zpub);
+    }
+
+    #[test]
+    fn custom_version() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+        let sk = ExtendedPrivKey::derive(seed, "m/0'").unwrap();
+        let version = Version::Custom {
+            private: [0x01, 0x02, 0x03, 0x04],
+            public: [0x05, 0x06, 0x07, 0x08],
+        };
+
+        let xprv = sk.to_string_with_version(version);
+
+        assert_eq!(ExtendedPrivKey::from_str(&xprv), Err(Error::UnknownVersion));
+        assert_eq!(ExtendedPrivKey::from_str_with_version(&xprv, Version::Mainnet), Err(Error::UnknownVersion));
+
+        let parsed = ExtendedPrivKey::from_str_with_version(&xprv, version).unwrap();
+        assert_eq!(parsed.version(), version);
+        assert_eq!(parsed.to_string(), xprv);
+        assert_eq!(parsed.to_string_with_version(Version::Mainnet), sk.to_string());
+
+        let xpub = ExtendedPubKey::from_private(&sk).to_string_with_version(version);
+
+        assert_eq!(ExtendedPubKey::from_str(&xpub), Err(Error::UnknownVersion));
+        assert_eq!(ExtendedPubKey::from_str_with_version(&xpub, version).unwrap().version(), version);
+    }
+
+    #[test]
+    fn key_origin() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+
+        let master = ExtendedPrivKey::derive(seed, "m").unwrap();
+
+        assert_eq!(master.depth(), 0);
+        assert_eq!(master.parent_fingerprint(), [0; 4]);
+        assert_eq!(master.child_number(), ChildNumber::non_hardened_from_u32(0));
+        assert_eq!(master.fingerprint(), [0x34, 0x42, 0x19, 0x3e]);
+
+        let account = master.child(ChildNumber::hardened_from_u32(0)).unwrap();
+
+        assert_eq!(account.depth(), 1);
+        assert_eq!(account.parent_fingerprint(), master.fingerprint());
+        assert_eq!(account.child_number(), ChildNumber::hardened_from_u32(0));
+        assert_eq!(account.fingerprint(), [0x5c, 0x1b, 0xd6, 0x48]);
+
+        let xpub = ExtendedPubKey::from_private(&account).child(ChildNumber::non_hardened_from_u32(1)).unwrap();
+
+        assert_eq!(xpub.depth(), 2);
+        assert_eq!(xpub.parent_fingerprint(), account.fingerprint());
+        assert_eq!(xpub.child_number(), ChildNumber::non_hardened_from_u32(1));
+        assert_eq!(xpub.fingerprint(), [0xbe, 0xf5, 0xa2, 0xf9]);
+    }
+
+    /// Wraps `tweak` so that the first HMAC result is rejected as invalid.
+    fn reject_first<Key>(mut tweak: impl FnMut(&[u8]) -> Result<Key, Error>) -> impl FnMut(&[u8]) -> Result<Key, Error> {
+        let mut first = true;
+
+        move |il| {
+            if std::mem::replace(&mut first, false) {
+                Err(Error::InvalidChildKey)
+            } else {
+                tweak(il)
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_child_key_policies() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+        let parent = ExtendedPrivKey::derive(seed, "m/0'").unwrap();
+        let public_key = parent.public_key();
+        let child = ChildNumber::non_hardened_from_u32(0);
+        let tweak = |tweak: &[u8]| Secp256k1::tweak_add(&parent.secret_key, tweak);
+
+        let result = ckd(&parent.chain_code, &public_key, child, InvalidKeyPolicy::Error, reject_first(tweak));
+        assert_eq!(result.err(), Some(Error::InvalidChildKey));
+
+        let (secret_key, chain_code, child_number) =
+            ckd(&parent.chain_code, &public_key, child, InvalidKeyPolicy::NextIndex, reject_first(tweak)).unwrap();
+        let next = parent.child(ChildNumber::non_hardened_from_u32(1)).unwrap();
+
+        assert_eq!(child_number, ChildNumber::non_hardened_from_u32(1));
+        assert_eq!(Secp256k1::serialize_secret(&secret_key), Secp256k1::serialize_secret(&next.secret_key));
+        assert_eq!(chain_code, next.chain_code);
+
+        let (secret_key, chain_code, child_number) =
+            ckd(&parent.chain_code, &public_key, child, InvalidKeyPolicy::Rehash, reject_first(tweak)).unwrap();
+        let rejected = ckd_hmac(&parent.chain_code, &[&public_key], child).unwrap();
+        let retried = ckd_hmac(&parent.chain_code, &[&[1], &rejected[32..]], child).unwrap();
+
+        assert_eq!(child_number, child);
+        let expected = Secp256k1::tweak_add(&parent.secret_key, &retried[..32]).unwrap();
+        assert_eq!(Secp256k1::serialize_secret(&secret_key), Secp256k1::serialize_secret(&expected));
+        assert_eq!(&chain_code[..], &retried[32..]);
+    }
+
+    #[test]
+    fn next_index_gives_up_at_last_index() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+        let parent = ExtendedPrivKey::derive(seed, "m").unwrap();
+        let public_key = parent.public_key();
+        let last = ChildNumber::non_hardened_from_u32(0x7FFF_FFFF);
+        let tweak = |tweak: &[u8]| Secp256k1::tweak_add(&parent.secret_key, tweak);
+
+        let result = ckd(&parent.chain_code, &public_key, last, InvalidKeyPolicy::NextIndex, reject_first(tweak));
+        assert_eq!(result.err(), Some(Error::InvalidChildNumber));
+
+        // Valid keys are unaffected by the policy.
+        assert_eq!(
+            parent.child_with_policy(last, InvalidKeyPolicy::NextIndex).unwrap(),
+            parent.child(last).unwrap()
+        );
+    }
+
+    #[test]
+    fn cached_public_key() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+        let key = ExtendedPrivKey::derive(seed, "m/0'").unwrap();
+        let fresh = key.clone();
+
+        assert!(fresh.public_key.get().is_none());
+        assert_eq!(key.public_key(), ExtendedPubKey::from_private(&fresh).public_key());
+        assert_eq!(fresh.public_key.get(), Some(&key.public_key()));
+        assert_eq!(ExtendedPubKey::from_private(&key), ExtendedPubKey::from_private(&fresh));
+        assert_eq!(key, fresh);
+        assert_eq!(key.child(ChildNumber::from(1)), fresh.child(ChildNumber::from(1)));
+    }
+
+    #[test]
+    fn public_root() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+
+        assert_eq!(ExtendedPrivKey::derive(seed, "M/0/1"), Err(Error::PublicPathRoot));
+        assert_eq!(ExtendedPrivKey::derive(seed, "M"), Err(Error::PublicPathRoot));
+        assert_eq!(ExtendedPrivKey::derive(seed, "0/1"), ExtendedPrivKey::derive(seed, "m/0/1"));
+    }
+
+    #[test]
+    fn redacted_debug() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+        let key = ExtendedPrivKey::derive(seed, "m/0'/1").unwrap();
+        let debug = format!("{:?}", key);
+
+        assert_eq!(debug, "GenericExtendedPrivKey { curve: secp256k1, version: Mainnet, depth: 2, parent_fingerprint: 5c1bd648, child_number: 1, fingerprint: bef5a2f9, .. }");
+        assert!(!format!("{:#?}", key).contains(&hex(&*key.secret())));
+        assert!(!debug.contains(&hex(&key.chain_code[..])));
+
+        let xpub = ExtendedPubKey::from_private(&key);
+
+        assert_eq!(
+            format!("{:?}", xpub),
+            "GenericExtendedPubKey { curve: secp256k1, version: Mainnet, depth: 2, parent_fingerprint: 5c1bd648, child_number: 1, \
+             public_key: 03501e454bf00751f24b1b489aa925215d66af2234e3891c3b21a52bedb3cd711c, fingerprint: bef5a2f9, .. }"
+        );
+    }
+
+    #[test]
+    fn constant_time_eq() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+        let key = ExtendedPrivKey::derive(seed, "m/0'").unwrap();
+        let mut other = key.clone();
+
+        assert_eq!(key, other);
+
+        other.chain_code = Protected::default();
+        assert_ne!(key, other);
+        assert_ne!(key, key.child(ChildNumber::from(0)).unwrap());
+        assert_ne!(ExtendedPrivKey::derive(seed, "m/0").unwrap(), ExtendedPrivKey::derive(seed, "m/1").unwrap());
+    }
+
+    #[test]
+    fn erase() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+        let mut key = ExtendedPrivKey::derive(seed, "m/0'").unwrap();
+        let secret = key.secret();
+
+        key.erase();
+        assert_ne!(key.secret(), secret);
+    }
+
+    #[test]
+    fn invalid_xprv() {
+        let vectors = [
+            // pubkey version / prvkey mismatch
+            ("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6LBpB85b3D2yc8sfvZU521AAwdZafEz7mnzBBsz4wKY5fTtTQBm", Error::UnknownVersion),
+            // prvkey version / pubkey mismatch
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFGTQQD3dC4H2D5GBj7vWvSQaaBv5cxi9gafk7NF3pnBju6dwKvH", Error::InvalidPrivateKeyPadding),
+            // invalid prvkey prefix 01
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFAzHGBP2UuGCqWLTAPLcMtD9y5gkZ6Eq3Rjuahrv17fEQ3Qen6J", Error::InvalidPrivateKeyPadding),
+            // invalid prvkey prefix 04
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFGpWnsj83BHtEy5Zt8CcDr1UiRXuWCmTQLxEK9vbz5gPstX92JQ", Error::InvalidPrivateKeyPadding),
+            // zero depth with non-zero parent fingerprint
+            ("xprv9s2SPatNQ9Vc6GTbVMFPFo7jsaZySyzk7L8n2uqKXJen3KUmvQNTuLh3fhZMBoG3G4ZW1N2kZuHEPY53qmbZzCHshoQnNf4GvELZfqTUrcv", Error::ZeroDepthParentFingerprint),
+            // zero depth with non-zero index
+            ("xprv9s21ZrQH4r4TsiLvyLXqM9P7k1K3EYhA1kkD6xuquB5i39AU8KF42acDyL3qsDbU9NmZn6MsGSUYZEsuoePmjzsB3eFKSUEh3Gu1N3cqVUN", Error::ZeroDepthChildNumber),
+            // unknown extended key version
+            ("DMwo58pR1QLEFihHiXPVykYB6fJmsTeHvyTp7hRThAtCX8CvYzgPcn8XnmdfHGMQzT7ayAmfo4z3gY5KfbrZWZ6St24UVf2Qgo6oujFktLHdHY4", Error::UnknownVersion),
+            // private key 0 not in 1..n-1
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzF93Y5wvzdUayhgkkFoicQZcP3y52uPPxFnfoLZB21Teqt1VvEHx", Error::InvalidSecretKey),
+            // private key n not in 1..n-1
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFAzHGBP2UuGCqWLTAPLcMtD5SDKr24z3aiUvKr9bJpdrcLg1y3G", Error::InvalidSecretKey),
+            // invalid checksum
+            ("xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHL", Error::InvalidChecksum),
+        ];
+
+        for (xprv, error) in vectors.iter() {
+            assert_eq!(ExtendedPrivKey::from_str(xprv), Err(error.clone()), "{} should be rejected", xprv);
+        }
+    }
+
+    #[test]
+    fn invalid_xpub() {
+        let vectors = [
+            // pubkey version / invalid pubkey prefix 04
+            ("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6Txnt3siSujt9RCVYsx4qHZGc62TG4McvMGcAUjeuwZdduYEvFn", Error::InvalidPublicKey),
+            // invalid pubkey prefix 01
+            ("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6N8ZMMXctdiCjxTNq964yKkwrkBJJwpzZS4HS2fxvyYUA4q2Xe4", Error::InvalidPublicKey),
+            // zero depth with non-zero parent fingerprint
+            ("xpub661no6RGEX3uJkY4bNnPcw4URcQTrSibUZ4NqJEw5eBkv7ovTwgiT91XX27VbEXGENhYRCf7hyEbWrR3FewATdCEebj6znwMfQkhRYHRLpJ", Error::ZeroDepthParentFingerprint),
+            // zero depth with non-zero index
+            ("xpub661MyMwAuDcm6CRQ5N4qiHKrJ39Xe1R1NyfouMKTTWcguwVcfrZJaNvhpebzGerh7gucBvzEQWRugZDuDXjNDRmXzSZe4c7mnTK97pTvGS8", Error::ZeroDepthChildNumber),
+            // unknown extended key version
+            ("DMwo58pR1QLEFihHiXPVykYB6fJmsTeHvyTp7hRThAtCX8CvYzgPcn8XnmdfHPmHJiEDXkTiJTVV9rHEBUem2mwVbbNfvT2MTcAqj3nesx8uBf9", Error::UnknownVersion),
+            // invalid pubkey 020000000000000000000000000000000000000000000000000000000000000007
+            ("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6Q5JXayek4PRsn35jii4veMimro1xefsM58PgBMrvdYre8QyULY", Error::InvalidPublicKey),
+            // prvkey version / pubkey mismatch
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFGTQQD3dC4H2D5GBj7vWvSQaaBv5cxi9gafk7NF3pnBju6dwKvH", Error::UnknownVersion),
+        ];
+
+        for (xpub, error) in vectors.iter() {
+            assert_eq!(ExtendedPubKey::from_str(xpub), Err(error.clone()), "{} should be rejected", xpub);
+        }
+    }
+
+    #[test]
+    fn public_child_matches_private_child() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+
+        let parent = ExtendedPrivKey::derive(seed, "m/0'").unwrap();
+        let xpub = ExtendedPubKey::from_private(&parent).child(ChildNumber::non_hardened_from_u32(1)).unwrap();
+
+        assert_eq!(xpub, ExtendedPubKey::from_private(&parent.child(ChildNumber::non_hardened_from_u32(1)).unwrap()));
+        assert_eq!(&xpub.public_key()[..], &b"\x03\x50\x1e\x45\x4b\xf0\x07\x51\xf2\x4b\x1b\x48\x9a\xa9\x25\x21\x5d\x66\xaf\x22\x34\xe3\x89\x1c\x3b\x21\xa5\x2b\xed\xb3\xcd\x71\x1c"[..]);
+
+        let parent = ExtendedPrivKey::derive(seed, "m/0'/1/2'").unwrap();
+        let xpub = ExtendedPubKey::from_private(&parent)
+            .child(ChildNumber::non_hardened_from_u32(2)).unwrap()
+            .child(ChildNumber::non_hardened_from_u32(1000000000)).unwrap();
+
+        assert_eq!(&xpub.public_key()[..], &b"\x02\x2a\x47\x14\x24\xda\x5e\x65\x74\x99\xd1\xff\x51\xcb\x43\xc4\x74\x81\xa0\x3b\x1e\x77\xf9\x51\xfe\x64\xce\xc9\xf5\xa4\x8f\x70\x11"[..]);
+    }
+
+    #[test]
+    fn public_child_from_master() {
+        let seed = b"\xff\xfc\xf9\xf6\xf3\xf0\xed\xea\xe7\xe4\xe1\xde\xdb\xd8\xd5\xd2\xcf\xcc\xc9\xc6\xc3\xc0\xbd\xba\xb7\xb4\xb1\xae\xab\xa8\xa5\xa2\x9f\x9c\x99\x96\x93\x90\x8d\x8a\x87\x84\x81\x7e\x7b\x78\x75\x72\x6f\x6c\x69\x66\x63\x60\x5d\x5a\x57\x54\x51\x4e\x4b\x48\x45\x42";
 
-        let secret_key = SecretKey::from_raw(&account.secret()).unwrap();
-        let public_key = secret_key.public();
+        let master = ExtendedPubKey::from_private(&ExtendedPrivKey::derive(seed, "m").unwrap());
+        let xpub = master.child(ChildNumber::non_hardened_from_u32(0)).unwrap();
 
-        assert_eq!(expected_address, public_key.address(), "Address is invalid");
+        assert_eq!(&xpub.public_key()[..], &b"\x02\xfc\x9e\x5a\xf0\xac\x8d\x9b\x3c\xec\xfe\x2a\x88\x8e\x21\x17\xba\x3d\x08\x9d\x85\x85\x88\x6c\x9c\x82\x6b\x6b\x22\xa9\x8d\x12\xea"[..]);
+        assert_eq!(master.child(ChildNumber::hardened_from_u32(0)), Err(Error::HardenedPublicDerivation));
     }
 }
@@ -1,11 +1,14 @@
 use super::Error;
 
+use std::convert::TryFrom;
+use std::fmt;
+use std::iter::FromIterator;
 use std::str::FromStr;
 
 const HARDENED_BIT: u32 = 1 << 31;
 
 /// A child number for a derived key
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct ChildNumber(u32);
 
 impl ChildNumber {
@@ -28,57 +31,352 @@ impl ChildNumber {
 	pub fn non_hardened_from_u32(index: u32) -> Self {
 		ChildNumber(index)
 	}
+
+	/// The index without the hardened bit.
+	pub fn index(&self) -> u32 {
+		self.0 & !HARDENED_BIT
+	}
+
+	/// The next child number of the same kind, or `None` if the index is
+	/// already the last one.
+	pub fn increment(&self) -> Option<Self> {
+		let index = (self.0 & !HARDENED_BIT) + 1;
+
+		if index & HARDENED_BIT == 0 {
+			Some(ChildNumber(index | (self.0 & HARDENED_BIT)))
+		} else {
+			None
+		}
+	}
+}
+
+impl From<u32> for ChildNumber {
+	fn from(number: u32) -> Self {
+		ChildNumber(number)
+	}
+}
+
+impl From<ChildNumber> for u32 {
+	fn from(child: ChildNumber) -> u32 {
+		child.0
+	}
 }
 
+/// Parses a decimal index optionally followed by a `'`, `h` or `H`
+/// hardened marker.
 impl FromStr for ChildNumber {
     type Err = Error;
 
     fn from_str(child: &str) -> Result<ChildNumber, Error> {
-    	let (child, mask) = if child.ends_with('\'') {
-    		(&child[..child.len() - 1], HARDENED_BIT)
-    	} else {
-    		(child, 0)
-    	};
-
-        let index: u32 = child.parse().map_err(|_| Error::InvalidChildNumber)?;
-
-        if index & HARDENED_BIT == 0 {
-        	Ok(ChildNumber(index | mask))
-        } else {
-        	Err(Error::InvalidChildNumber)
-        }
+        parse_child(child).map_err(|_| Error::InvalidChildNumber)
     }
 }
 
+pub(crate) fn parse_child(child: &str) -> Result<ChildNumber, PathErrorKind> {
+	if child.is_empty() {
+		return Err(PathErrorKind::EmptySegment);
+	}
+
+	// Checking the digits ourselves, `u32::from_str` would also accept a leading `+`.
+	let digits = child.bytes().take_while(u8::is_ascii_digit).count();
+	let (index, marker) = child.split_at(digits);
+
+	if index.is_empty() {
+		return Err(PathErrorKind::InvalidIndex);
+	}
+
+	let mask = match marker {
+		"" => 0,
+		"'" | "h" | "H" => HARDENED_BIT,
+		_ => return Err(PathErrorKind::BadHardenedMarker),
+	};
+
+	match index.parse::<u32>() {
+		Ok(index) if index & HARDENED_BIT == 0 => Ok(ChildNumber(index | mask)),
+		_ => Err(PathErrorKind::Overflow),
+	}
+}
+
+/// Formats the index followed by `'` if hardened, or by `h` when the
+/// alternate flag is set (`{:#}`).
+impl fmt::Display for ChildNumber {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.is_hardened() {
+			let marker = if f.alternate() { 'h' } else { '\'' };
+
+			write!(f, "{}{}", self.0 & !HARDENED_BIT, marker)
+		} else {
+			write!(f, "{}", self.0)
+		}
+	}
+}
+
+/// Why a derivation path failed to parse.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PathErrorKind {
+	/// Two consecutive slashes, or a leading or trailing one.
+	EmptySegment,
+	/// The segment doesn't start with a decimal index.
+	InvalidIndex,
+	/// The index is followed by something other than one `'`, `h` or `H`.
+	BadHardenedMarker,
+	/// The index is 2^31 or above.
+	Overflow,
+	/// The path has no `m` or `M` root where one is required.
+	MissingRoot,
+	/// A malformed `<a;b>` alternative, `[a-b]` range or `*` wildcard in a
+	/// path template.
+	InvalidTemplate,
+}
+
+/// A derivation path parse failure, pointing at the offending segment.
+///
+/// Segments are numbered from 0, the root being segment 0 when present.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PathError {
+	segment: usize,
+	text: String,
+	kind: PathErrorKind,
+}
+
+impl PathError {
+	pub fn new(segment: usize, text: &str, kind: PathErrorKind) -> PathError {
+		PathError { segment, text: text.to_owned(), kind }
+	}
+
+	pub fn segment(&self) -> usize {
+		self.segment
+	}
+
+	/// Text of the offending segment.
+	pub fn text(&self) -> &str {
+		&self.text
+	}
+
+	pub fn kind(&self) -> PathErrorKind {
+		self.kind
+	}
+}
+
+impl fmt::Display for PathErrorKind {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			PathErrorKind::EmptySegment => "empty segment",
+			PathErrorKind::InvalidIndex => "not a decimal index",
+			PathErrorKind::BadHardenedMarker => "bad hardened marker, expected one of ', h or H",
+			PathErrorKind::Overflow => "index must be below 2^31",
+			PathErrorKind::MissingRoot => "missing m or M root",
+			PathErrorKind::InvalidTemplate => "malformed alternatives, range or wildcard",
+		})
+	}
+}
+
+impl fmt::Display for PathError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "segment {} ({:?}): {}", self.segment, self.text, self.kind)
+	}
+}
+
+impl std::error::Error for PathError {}
+
+/// What a derivation path starts from.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum PathRoot {
+	/// The master private key, written `m`.
+	#[default]
+	Private,
+	/// The master public key, written `M`.
+	Public,
+	/// No root, the path continues from whichever key it is applied to.
+	Relative,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct DerivationPath {
+    root: PathRoot,
     path: Vec<ChildNumber>,
 }
 
+/// Parses `m/44'/60'/0'/0/0`, `M/44h/60h/0h` or a relative `0/1`.
+///
+/// A malformed segment fails with `Error::InvalidPath`.
 impl FromStr for DerivationPath {
     type Err = Error;
 
     fn from_str(path: &str) -> Result<DerivationPath, Error> {
-        let mut path = path.split('/');
+        let (root, segments) = split_root(path);
 
-        if path.next() != Some("m") {
-            return Err(Error::InvalidDerivationPath);
-        }
+        let path = segments
+            .map(|(segment, child)| {
+                parse_child(child).map_err(|kind| Error::InvalidPath(PathError::new(segment, child, kind)))
+            })
+            .collect::<Result<Vec<ChildNumber>, Error>>()?;
 
-        Ok(DerivationPath {
-            path: path.map(str::parse).collect::<Result<Vec<ChildNumber>, Error>>()?
-        })
+        Ok(DerivationPath { root, path })
     }
 }
 
-impl DerivationPath {
-	pub fn as_ref(&self) -> &[ChildNumber] {
+/// Splits a path into its root and numbered child segments. An empty
+/// string is an empty relative path.
+pub(crate) fn split_root(path: &str) -> (PathRoot, impl Iterator<Item = (usize, &str)>) {
+	let root = match path.split('/').next() {
+		Some("m") => PathRoot::Private,
+		Some("M") => PathRoot::Public,
+		_ => PathRoot::Relative,
+	};
+	let skip = if root != PathRoot::Relative || path.is_empty() { 1 } else { 0 };
+
+	(root, path.split('/').enumerate().skip(skip))
+}
+
+/// Formats the path as `m/44'/60'/0'/0/0`, or `m/44h/60h/0h/0/0` when the
+/// alternate flag is set (`{:#}`). Relative paths are written without a root.
+impl fmt::Display for DerivationPath {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut separator = match self.root {
+			PathRoot::Private => { f.write_str("m")?; "/" },
+			PathRoot::Public => { f.write_str("M")?; "/" },
+			PathRoot::Relative => "",
+		};
+
+		for child in self.path.iter() {
+			f.write_str(separator)?;
+
+			if f.alternate() {
+				write!(f, "{:#}", child)?;
+			} else {
+				write!(f, "{}", child)?;
+			}
+
+			separator = "/";
+		}
+
+		Ok(())
+	}
+}
+
+impl AsRef<[ChildNumber]> for DerivationPath {
+	fn as_ref(&self) -> &[ChildNumber] {
 		&self.path
 	}
+}
+
+impl DerivationPath {
+	pub fn new(root: PathRoot, path: Vec<ChildNumber>) -> DerivationPath {
+		DerivationPath { root, path }
+	}
+
+	pub fn root(&self) -> PathRoot {
+		self.root
+	}
+
+	pub fn is_relative(&self) -> bool {
+		self.root == PathRoot::Relative
+	}
+
+	/// Fails with `Error::PublicPathRoot` if the path starts from the master
+	/// public key, which private derivation can't honour.
+	pub(crate) fn check_private(&self) -> Result<(), Error> {
+		match self.root {
+			PathRoot::Public => Err(Error::PublicPathRoot),
+			_ => Ok(()),
+		}
+	}
 
 	pub fn iter(&self) -> impl Iterator<Item = &ChildNumber> {
 		self.path.iter()
 	}
+
+	/// Number of children in the path.
+	pub fn len(&self) -> usize {
+		self.path.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.path.is_empty()
+	}
+
+	/// Depth of the key an absolute path leads to, the same as `len`.
+	pub fn depth(&self) -> usize {
+		self.path.len()
+	}
+
+	pub fn last(&self) -> Option<ChildNumber> {
+		self.path.last().copied()
+	}
+
+	pub fn push(&mut self, child: ChildNumber) {
+		self.path.push(child);
+	}
+
+	/// A copy of this path with `child` appended.
+	pub fn child(&self, child: ChildNumber) -> DerivationPath {
+		let mut path = self.clone();
+
+		path.push(child);
+		path
+	}
+
+	/// This path without its last child, `None` if it is already empty.
+	pub fn parent(&self) -> Option<DerivationPath> {
+		let (_, parent) = self.path.split_last()?;
+
+		Some(DerivationPath { root: self.root, path: parent.to_vec() })
+	}
+
+	/// Whether `other` has the same root and strictly extends this path.
+	pub fn is_ancestor_of(&self, other: &DerivationPath) -> bool {
+		self.len() < other.len() && other.strip_prefix(self).is_some()
+	}
+
+	/// The relative path leading from `prefix` to this path, `None` if
+	/// `prefix` has a different root or isn't a prefix of this path.
+	pub fn strip_prefix(&self, prefix: &DerivationPath) -> Option<DerivationPath> {
+		if self.root != prefix.root {
+			return None;
+		}
+
+		let path = self.path.strip_prefix(&prefix.path[..])?;
+
+		Some(DerivationPath { root: PathRoot::Relative, path: path.to_vec() })
+	}
+}
+
+/// Collects children into an absolute `m/...` path.
+impl FromIterator<ChildNumber> for DerivationPath {
+	fn from_iter<I: IntoIterator<Item = ChildNumber>>(children: I) -> DerivationPath {
+		DerivationPath { root: PathRoot::Private, path: children.into_iter().collect() }
+	}
+}
+
+impl Extend<ChildNumber> for DerivationPath {
+	fn extend<I: IntoIterator<Item = ChildNumber>>(&mut self, children: I) {
+		self.path.extend(children);
+	}
+}
+
+impl<'a> Extend<&'a ChildNumber> for DerivationPath {
+	fn extend<I: IntoIterator<Item = &'a ChildNumber>>(&mut self, children: I) {
+		self.path.extend(children);
+	}
+}
+
+impl IntoIterator for DerivationPath {
+	type Item = ChildNumber;
+	type IntoIter = std::vec::IntoIter<ChildNumber>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.path.into_iter()
+	}
+}
+
+impl<'a> IntoIterator for &'a DerivationPath {
+	type Item = &'a ChildNumber;
+	type IntoIter = std::slice::Iter<'a, ChildNumber>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.path.iter()
+	}
 }
 
 pub trait IntoDerivationPath {
@@ -97,15 +395,308 @@ impl IntoDerivationPath for &str {
 	}
 }
 
+/// Declares the `KnownCoinType` enum and its lookup table from
+/// `Variant = index, symbol, name;` entries. The entries are picked by hand
+/// from SLIP-0044 and copied verbatim, add a line to support another coin.
+macro_rules! known_coin_types {
+	($($variant:ident = $index:expr, $symbol:expr, $name:expr;)*) => {
+		/// A curated subset of the coin types registered in SLIP-0044, used
+		/// as the second level of BIP44 paths.
+		///
+		/// Only widely used coins are listed, not the whole registry. Coin
+		/// types missing here can still be passed to `Bip44Path::new` as
+		/// plain indices.
+		#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+		pub enum KnownCoinType {
+			$($variant,)*
+		}
+
+		impl KnownCoinType {
+			/// Every listed coin type, ordered by index.
+			pub const LISTED: &'static [KnownCoinType] = &[$(KnownCoinType::$variant,)*];
+
+			/// The unhardened SLIP-0044 index.
+			pub fn index(&self) -> u32 {
+				match self {
+					$(KnownCoinType::$variant => $index,)*
+				}
+			}
+
+			/// Ticker symbol, `None` for entries such as `Testnet` that have none.
+			pub fn symbol(&self) -> Option<&'static str> {
+				match self {
+					$(KnownCoinType::$variant => $symbol,)*
+				}
+			}
+
+			pub fn name(&self) -> &'static str {
+				match self {
+					$(KnownCoinType::$variant => $name,)*
+				}
+			}
+		}
+	};
+}
+
+known_coin_types! {
+	Bitcoin = 0, Some("BTC"), "Bitcoin";
+	Testnet = 1, None, "Testnet (all coins)";
+	Litecoin = 2, Some("LTC"), "Litecoin";
+	Dogecoin = 3, Some("DOGE"), "Dogecoin";
+	Reddcoin = 4, Some("RDD"), "Reddcoin";
+	Dash = 5, Some("DASH"), "Dash";
+	Peercoin = 6, Some("PPC"), "Peercoin";
+	Namecoin = 7, Some("NMC"), "Namecoin";
+	Viacoin = 14, Some("VIA"), "Viacoin";
+	DigiByte = 20, Some("DGB"), "DigiByte";
+	Monacoin = 22, Some("MONA"), "Monacoin";
+	Vertcoin = 28, Some("VTC"), "Vertcoin";
+	Decred = 42, Some("DCR"), "Decred";
+	Ethereum = 60, Some("ETH"), "Ethereum";
+	EthereumClassic = 61, Some("ETC"), "Ether Classic";
+	Icon = 74, Some("ICX"), "ICON";
+	Verge = 77, Some("XVG"), "Verge";
+	Cosmos = 118, Some("ATOM"), "Atom";
+	Horizen = 121, Some("ZEN"), "Horizen";
+	Monero = 128, Some("XMR"), "Monero";
+	Zcash = 133, Some("ZEC"), "Zcash";
+	Lisk = 134, Some("LSK"), "Lisk";
+	Ripple = 144, Some("XRP"), "Ripple";
+	BitcoinCash = 145, Some("BCH"), "Bitcoin Cash";
+	Stellar = 148, Some("XLM"), "Stellar Lumens";
+	BitcoinGold = 156, Some("BTG"), "Bitcoin Gold";
+	Eos = 194, Some("EOS"), "EOS";
+	Tron = 195, Some("TRX"), "Tron";
+	BitcoinSv = 236, Some("BSV"), "BitcoinSV";
+	Algorand = 283, Some("ALGO"), "Algorand";
+	Terra = 330, Some("LUNA"), "Terra";
+	Polkadot = 354, Some("DOT"), "Polkadot";
+	Near = 397, Some("NEAR"), "NEAR Protocol";
+	Kusama = 434, Some("KSM"), "Kusama";
+	Filecoin = 461, Some("FIL"), "Filecoin";
+	Solana = 501, Some("SOL"), "Solana";
+	MultiversX = 508, Some("EGLD"), "MultiversX";
+	Aptos = 637, Some("APT"), "Aptos";
+	Binance = 714, Some("BNB"), "Binance";
+	Sui = 784, Some("SUI"), "Sui";
+	VeChain = 818, Some("VET"), "VeChain Token";
+	Polygon = 966, Some("MATIC"), "Polygon";
+	Tezos = 1729, Some("XTZ"), "Tezos";
+	Cardano = 1815, Some("ADA"), "Cardano";
+	Avalanche = 9000, Some("AVAX"), "Avalanche";
+	Celo = 52752, Some("CELO"), "Celo";
+}
+
+/// Lookups only search the listed coins and return `None` for any other
+/// coin, even one registered in SLIP-0044.
+impl KnownCoinType {
+	pub fn from_index(index: u32) -> Option<KnownCoinType> {
+		KnownCoinType::LISTED.iter().copied().find(|coin| coin.index() == index)
+	}
+
+	/// Looks a listed coin type up by its ticker symbol, ignoring case.
+	pub fn from_symbol(symbol: &str) -> Option<KnownCoinType> {
+		KnownCoinType::LISTED.iter().copied().find(|coin| coin.symbol().is_some_and(|s| s.eq_ignore_ascii_case(symbol)))
+	}
+
+	/// Looks a listed coin type up by its SLIP-0044 name, ignoring case.
+	pub fn from_name(name: &str) -> Option<KnownCoinType> {
+		KnownCoinType::LISTED.iter().copied().find(|coin| coin.name().eq_ignore_ascii_case(name))
+	}
+}
+
+impl From<KnownCoinType> for u32 {
+	fn from(coin: KnownCoinType) -> u32 {
+		coin.index()
+	}
+}
+
+impl From<KnownCoinType> for ChildNumber {
+	fn from(coin: KnownCoinType) -> ChildNumber {
+		ChildNumber::hardened_from_u32(coin.index())
+	}
+}
+
+/// A path of the form `m / purpose' / coin_type' / account' / change / address_index`,
+/// as used by BIP44 and the BIP49, BIP84 and BIP86 wallets derived from it.
+///
+/// Indices are stored without the hardened bit, the first three levels are
+/// always hardened and the last two never are. The purpose is one of 44, 49,
+/// 84 and 86, and change is 0 for external or 1 for internal addresses.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Bip44Path {
+	purpose: u32,
+	coin_type: u32,
+	account: u32,
+	change: u32,
+	address_index: u32,
+}
+
+impl Bip44Path {
+	/// Creates a path with the given purpose and raw SLIP-0044 coin type,
+	/// failing with `Error::InvalidChildNumber` if any index doesn't fit in
+	/// 31 bits, and with `Error::InvalidDerivationPath` if the purpose isn't
+	/// 44, 49, 84 or 86 or change isn't 0 or 1.
+	pub fn new(purpose: u32, coin_type: u32, account: u32, change: u32, address_index: u32) -> Result<Self, Error> {
+		for index in [purpose, coin_type, account, change, address_index].iter() {
+			if index & HARDENED_BIT != 0 {
+				return Err(Error::InvalidChildNumber);
+			}
+		}
+
+		if ![44, 49, 84, 86].contains(&purpose) || change > 1 {
+			return Err(Error::InvalidDerivationPath);
+		}
+
+		Ok(Bip44Path { purpose, coin_type, account, change, address_index })
+	}
+
+	/// Legacy P2PKH wallet path, `m/44'/coin_type'/account'/change/address_index`.
+	pub fn bip44(coin_type: KnownCoinType, account: u32, change: u32, address_index: u32) -> Result<Self, Error> {
+		Bip44Path::new(44, coin_type.index(), account, change, address_index)
+	}
+
+	/// P2SH-P2WPKH wallet path, `m/49'/coin_type'/account'/change/address_index`.
+	pub fn bip49(coin_type: KnownCoinType, account: u32, change: u32, address_index: u32) -> Result<Self, Error> {
+		Bip44Path::new(49, coin_type.index(), account, change, address_index)
+	}
+
+	/// P2WPKH wallet path, `m/84'/coin_type'/account'/change/address_index`.
+	pub fn bip84(coin_type: KnownCoinType, account: u32, change: u32, address_index: u32) -> Result<Self, Error> {
+		Bip44Path::new(84, coin_type.index(), account, change, address_index)
+	}
+
+	/// P2TR wallet path, `m/86'/coin_type'/account'/change/address_index`.
+	pub fn bip86(coin_type: KnownCoinType, account: u32, change: u32, address_index: u32) -> Result<Self, Error> {
+		Bip44Path::new(86, coin_type.index(), account, change, address_index)
+	}
+
+	pub fn purpose(&self) -> u32 {
+		self.purpose
+	}
+
+	pub fn coin_type(&self) -> u32 {
+		self.coin_type
+	}
+
+	/// The coin type, if the index is one of the listed `KnownCoinType`s.
+	pub fn coin(&self) -> Option<KnownCoinType> {
+		KnownCoinType::from_index(self.coin_type)
+	}
+
+	pub fn account(&self) -> u32 {
+		self.account
+	}
+
+	pub fn change(&self) -> u32 {
+		self.change
+	}
+
+	pub fn address_index(&self) -> u32 {
+		self.address_index
+	}
+
+	/// The same path with a different address index.
+	pub fn with_address_index(&self, address_index: u32) -> Result<Self, Error> {
+		Bip44Path::new(self.purpose, self.coin_type, self.account, self.change, address_index)
+	}
+}
+
+impl From<Bip44Path> for DerivationPath {
+	fn from(path: Bip44Path) -> DerivationPath {
+		DerivationPath {
+			root: PathRoot::Private,
+			path: vec![
+				ChildNumber::hardened_from_u32(path.purpose),
+				ChildNumber::hardened_from_u32(path.coin_type),
+				ChildNumber::hardened_from_u32(path.account),
+				ChildNumber::non_hardened_from_u32(path.change),
+				ChildNumber::non_hardened_from_u32(path.address_index),
+			],
+		}
+	}
+}
+
+impl TryFrom<&DerivationPath> for Bip44Path {
+	type Error = Error;
+
+	/// Fails with `Error::InvalidDerivationPath` unless the path starts from
+	/// the master private key `m`, has five levels with only the first three
+	/// hardened, and has a known purpose and a change of 0 or 1.
+	fn try_from(path: &DerivationPath) -> Result<Bip44Path, Error> {
+		if path.root() != PathRoot::Private {
+			return Err(Error::InvalidDerivationPath);
+		}
+
+		match path.path[..] {
+			[purpose, coin_type, account, change, address_index]
+				if purpose.is_hardened() && coin_type.is_hardened() && account.is_hardened()
+					&& change.is_normal() && address_index.is_normal() =>
+			{
+				Bip44Path::new(purpose.index(), coin_type.index(), account.index(), change.index(), address_index.index())
+			}
+			_ => Err(Error::InvalidDerivationPath),
+		}
+	}
+}
+
+impl TryFrom<DerivationPath> for Bip44Path {
+	type Error = Error;
+
+	fn try_from(path: DerivationPath) -> Result<Bip44Path, Error> {
+		Bip44Path::try_from(&path)
+	}
+}
+
+impl FromStr for Bip44Path {
+	type Err = Error;
+
+	/// Fails like `DerivationPath::from_str`, and with
+	/// `PathErrorKind::MissingRoot` on relative paths.
+	fn from_str(path: &str) -> Result<Bip44Path, Error> {
+		let parsed: DerivationPath = path.parse()?;
+
+		if parsed.is_relative() {
+			let first = path.split('/').next().unwrap_or_default();
+
+			return Err(Error::InvalidPath(PathError::new(0, first, PathErrorKind::MissingRoot)));
+		}
+
+		Bip44Path::try_from(&parsed)
+	}
+}
+
+impl fmt::Display for Bip44Path {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(&DerivationPath::from(*self), f)
+	}
+}
+
+impl IntoDerivationPath for Bip44Path {
+	fn into(self) -> Result<DerivationPath, Error> {
+		Ok(DerivationPath::from(self))
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 
 	#[test]
+	fn increment() {
+		assert_eq!(ChildNumber(0).increment(), Some(ChildNumber(1)));
+		assert_eq!(ChildNumber(HARDENED_BIT).increment(), Some(ChildNumber(HARDENED_BIT + 1)));
+		assert_eq!(ChildNumber(HARDENED_BIT - 1).increment(), None);
+		assert_eq!(ChildNumber(u32::MAX).increment(), None);
+	}
+
+	#[test]
+	#[allow(clippy::identity_op)]
 	fn derive_path() {
 		let path: DerivationPath = "m/44'/60'/0'/0".parse().unwrap();
 
 		assert_eq!(path, DerivationPath {
+			root: PathRoot::Private,
 			path: vec![
 				ChildNumber(44 | HARDENED_BIT),
 				ChildNumber(60 | HARDENED_BIT),
@@ -114,4 +705,204 @@ mod tests {
 			],
 		});
 	}
+
+	#[test]
+	fn hardened_markers() {
+		for child in ["44'", "44h", "44H"].iter() {
+			assert_eq!(child.parse(), Ok(ChildNumber(44 | HARDENED_BIT)));
+		}
+
+		for child in ["", "'", "h", "+1", " 1", "1 ", "-1", "1''", "1hh", "1x", "0x1", "2147483648", "2147483648'"].iter() {
+			assert_eq!(child.parse::<ChildNumber>(), Err(Error::InvalidChildNumber), "{:?} should be rejected", child);
+		}
+	}
+
+	#[test]
+	fn path_roots() {
+		let private: DerivationPath = "m/44h/60H/0'".parse().unwrap();
+		let public: DerivationPath = "M/44'/60'/0'".parse().unwrap();
+		let relative: DerivationPath = "44'/60'/0'".parse().unwrap();
+
+		assert_eq!(private.root(), PathRoot::Private);
+		assert_eq!(public.root(), PathRoot::Public);
+		assert_eq!(relative.root(), PathRoot::Relative);
+		assert!(relative.is_relative());
+
+		assert_eq!(private.as_ref(), public.as_ref());
+		assert_eq!(private.as_ref(), relative.as_ref());
+		assert_ne!(private, public);
+
+		assert_eq!("m".parse(), Ok(DerivationPath::default()));
+		assert_eq!("M".parse::<DerivationPath>().unwrap().as_ref(), &[]);
+		assert_eq!("".parse::<DerivationPath>().unwrap().root(), PathRoot::Relative);
+		assert_eq!("0".parse::<DerivationPath>().unwrap().as_ref(), &[ChildNumber(0)]);
+	}
+
+	#[test]
+	fn malformed_paths() {
+		use PathErrorKind::*;
+
+		let vectors = [
+			("m//0", 1, "", EmptySegment),
+			("m/0/", 2, "", EmptySegment),
+			("m/", 1, "", EmptySegment),
+			("/0", 0, "", EmptySegment),
+			("0/", 1, "", EmptySegment),
+			("m/0//1", 2, "", EmptySegment),
+			("+m/0", 0, "+m", InvalidIndex),
+			(" m/0", 0, " m", InvalidIndex),
+			("m /0", 0, "m ", InvalidIndex),
+			("m/+0", 1, "+0", InvalidIndex),
+			("m/ 0", 1, " 0", InvalidIndex),
+			("mm/0", 0, "mm", InvalidIndex),
+			("m/0/M", 2, "M", InvalidIndex),
+			("m/'", 1, "'", InvalidIndex),
+			("m/0/1h ", 2, "1h ", BadHardenedMarker),
+			("m/1''", 1, "1''", BadHardenedMarker),
+			("m/1x", 1, "1x", BadHardenedMarker),
+			("m/-1", 1, "-1", InvalidIndex),
+			("m/2147483648", 1, "2147483648", Overflow),
+			("m/0/99999999999'", 2, "99999999999'", Overflow),
+		];
+
+		for (path, segment, text, kind) in vectors.iter() {
+			let expected = Error::InvalidPath(PathError::new(*segment, text, *kind));
+
+			assert_eq!(path.parse::<DerivationPath>(), Err(expected), "{:?} should be rejected", path);
+		}
+	}
+
+	#[test]
+	fn path_error_display() {
+		let error = "m/44'/60'/0x".parse::<DerivationPath>().unwrap_err();
+
+		assert_eq!(error.to_string(), "invalid derivation path: segment 3 (\"0x\"): bad hardened marker, expected one of ', h or H");
+
+		let error = "44'/60'/0'/0/0".parse::<Bip44Path>().unwrap_err();
+
+		assert_eq!(error, Error::InvalidPath(PathError::new(0, "44'", PathErrorKind::MissingRoot)));
+		assert_eq!(error.to_string(), "invalid derivation path: segment 0 (\"44'\"): missing m or M root");
+	}
+
+	#[test]
+	fn path_manipulation() {
+		let account: DerivationPath = "m/84'/0'/0'".parse().unwrap();
+		let address: DerivationPath = "m/84'/0'/0'/1/5".parse().unwrap();
+
+		assert_eq!(account.len(), 3);
+		assert_eq!(address.depth(), 5);
+		assert!(!account.is_empty());
+		assert!(DerivationPath::default().is_empty());
+		assert_eq!(account.last(), Some(ChildNumber::hardened_from_u32(0)));
+		assert_eq!(DerivationPath::default().last(), None);
+
+		let relative = address.strip_prefix(&account).unwrap();
+
+		assert_eq!(relative.to_string(), "1/5");
+		assert!(relative.is_relative());
+		assert_eq!(address.strip_prefix(&address).unwrap(), "".parse().unwrap());
+		assert_eq!(account.strip_prefix(&address), None);
+		assert_eq!(address.strip_prefix(&"M/84'/0'/0'".parse().unwrap()), None);
+		assert_eq!(address.strip_prefix(&"m/84'/0'/1'".parse().unwrap()), None);
+
+		assert!(account.is_ancestor_of(&address));
+		assert!(!address.is_ancestor_of(&account));
+		assert!(!account.is_ancestor_of(&account));
+		assert!(!relative.is_ancestor_of(&address));
+
+		let mut extended = account.clone();
+		extended.extend(&relative);
+		assert_eq!(extended, address);
+
+		let mut pushed = account.child(ChildNumber::non_hardened_from_u32(1));
+		pushed.push(ChildNumber::non_hardened_from_u32(5));
+		assert_eq!(pushed, address);
+
+		assert_eq!(address.parent().unwrap().parent().unwrap(), account);
+		assert_eq!("M/0".parse::<DerivationPath>().unwrap().parent(), Some("M".parse().unwrap()));
+		assert_eq!(DerivationPath::default().parent(), None);
+
+		let collected: DerivationPath = address.iter().copied().collect();
+		assert_eq!(collected, address);
+
+		let children: Vec<ChildNumber> = address.clone().into_iter().collect();
+		assert_eq!(DerivationPath::new(PathRoot::Private, children), address);
+		assert_eq!((&address).into_iter().count(), 5);
+	}
+
+	#[test]
+	fn display() {
+		assert_eq!(ChildNumber(7).to_string(), "7");
+		assert_eq!(ChildNumber(7 | HARDENED_BIT).to_string(), "7'");
+		assert_eq!(format!("{:#}", ChildNumber(7 | HARDENED_BIT)), "7h");
+		assert_eq!(ChildNumber(u32::MAX).to_string(), "2147483647'");
+
+		assert_eq!(DerivationPath::default().to_string(), "m");
+		assert_eq!(Bip44Path::bip44(KnownCoinType::Ethereum, 0, 0, 0).unwrap().to_string(), "m/44'/60'/0'/0/0");
+		assert_eq!(format!("{:#}", Bip44Path::bip84(KnownCoinType::Bitcoin, 1, 0, 5).unwrap()), "m/84h/0h/1h/0/5");
+	}
+
+	#[test]
+	fn display_round_trip() {
+		let paths = ["m", "m/0", "m/0'", "m/44'/60'/0'/0/0", "m/2147483647'/2147483647", "m/0'/1/2'/2/1000000000", "M", "M/0/1", "", "0", "0'/1/2'"];
+
+		for path in paths.iter() {
+			let parsed: DerivationPath = path.parse().unwrap();
+
+			assert_eq!(&parsed.to_string(), path);
+			assert_eq!(format!("{:#}", parsed).parse(), Ok(parsed.clone()));
+			assert_eq!(format!("{:#}", parsed), path.replace('\'', "h"));
+		}
+	}
+
+	#[test]
+	fn bip44_path() {
+		let path = Bip44Path::bip44(KnownCoinType::Ethereum, 0, 0, 7).unwrap();
+		let derivation_path = DerivationPath::from(path);
+
+		assert_eq!(derivation_path, "m/44'/60'/0'/0/7".parse().unwrap());
+		assert_eq!(Bip44Path::try_from(&derivation_path), Ok(path));
+		assert_eq!("m/44'/60'/0'/0/7".parse(), Ok(path));
+		assert_eq!(path.with_address_index(8).unwrap(), "m/44'/60'/0'/0/8".parse().unwrap());
+
+		assert_eq!(Bip44Path::bip49(KnownCoinType::Testnet, 2, 1, 3).unwrap(), "m/49'/1'/2'/1/3".parse().unwrap());
+		assert_eq!(Bip44Path::bip84(KnownCoinType::Bitcoin, 0, 1, 0).unwrap(), "m/84'/0'/0'/1/0".parse().unwrap());
+		assert_eq!(Bip44Path::bip86(KnownCoinType::Bitcoin, 5, 0, 9).unwrap(), "m/86'/0'/5'/0/9".parse().unwrap());
+	}
+
+	#[test]
+	fn coin_types() {
+		assert_eq!(KnownCoinType::Ethereum.index(), 60);
+		assert_eq!(KnownCoinType::Solana.index(), 501);
+		assert_eq!(ChildNumber::from(KnownCoinType::Bitcoin), ChildNumber(HARDENED_BIT));
+		assert_eq!(KnownCoinType::from_index(60), Some(KnownCoinType::Ethereum));
+		assert_eq!(KnownCoinType::from_index(1 << 30), None);
+		assert_eq!(KnownCoinType::from_symbol("eth"), Some(KnownCoinType::Ethereum));
+		assert_eq!(KnownCoinType::from_symbol("BTC"), Some(KnownCoinType::Bitcoin));
+		assert_eq!(KnownCoinType::from_symbol(""), None);
+		assert_eq!(KnownCoinType::from_name("bitcoin cash"), Some(KnownCoinType::BitcoinCash));
+		assert_eq!(KnownCoinType::Testnet.symbol(), None);
+
+		for pair in KnownCoinType::LISTED.windows(2) {
+			assert!(pair[0].index() < pair[1].index(), "{:?} is out of order", pair[1]);
+		}
+
+		let path = Bip44Path::bip44(KnownCoinType::Ethereum, 0, 0, 0).unwrap();
+
+		assert_eq!(path, "m/44'/60'/0'/0/0".parse().unwrap());
+		assert_eq!(path.coin(), Some(KnownCoinType::Ethereum));
+	}
+
+	#[test]
+	fn invalid_bip44_path() {
+		assert_eq!(Bip44Path::new(44, HARDENED_BIT, 0, 0, 0), Err(Error::InvalidChildNumber));
+		assert_eq!(Bip44Path::bip84(KnownCoinType::Bitcoin, 0, 0, u32::MAX), Err(Error::InvalidChildNumber));
+		assert_eq!(Bip44Path::new(44, 60, 0, 2, 0), Err(Error::InvalidDerivationPath));
+		assert_eq!(Bip44Path::new(3, 60, 0, 0, 0), Err(Error::InvalidDerivationPath));
+		assert_eq!(Bip44Path::bip44(KnownCoinType::Ethereum, 0, 7, 0), Err(Error::InvalidDerivationPath));
+
+		for path in ["m", "m/44'/60'/0'/0", "m/44'/60'/0'/0/0/0", "m/44/60'/0'/0/0", "m/44'/60/0'/0/0", "m/44'/60'/0/0/0", "m/44'/60'/0'/0'/0", "m/44'/60'/0'/0/0'", "m/44'/60'/0'/7/0", "m/44'/60'/0'/2/0", "m/3'/60'/0'/0/0", "m/45'/0'/0'/0/0", "M/44'/60'/0'/0/0"].iter() {
+			assert_eq!(path.parse::<Bip44Path>(), Err(Error::InvalidDerivationPath), "{} should be rejected", path);
+		}
+	}
 }
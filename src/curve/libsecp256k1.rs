@@ -0,0 +1,60 @@
+use secp256k1::{PublicKey, SecretKey};
+
+use super::{Curve, Secp256k1};
+use crate::bip32::InvalidKeyPolicy;
+use crate::protected::Protected;
+use crate::Error;
+
+impl Curve for Secp256k1 {
+    const NAME: &'static str = "secp256k1";
+    const SEED_KEY: &'static [u8] = b"Bitcoin seed";
+    const INVALID_KEY_POLICY: InvalidKeyPolicy = InvalidKeyPolicy::Error;
+
+    type SecretKey = SecretKey;
+    type PublicKey = PublicKey;
+
+    fn parse_secret(bytes: &[u8]) -> Result<SecretKey, Error> {
+        SecretKey::parse_slice(bytes).map_err(|_| Error::InvalidSecretKey)
+    }
+
+    fn serialize_secret(secret_key: &SecretKey) -> Protected {
+        Protected::from(secret_key.serialize())
+    }
+
+    fn erase_secret(secret_key: &mut SecretKey) {
+        // `SecretKey` has no destructor, so the volatile write replaces the
+        // scalar with the default key of one without being optimised away.
+        unsafe { std::ptr::write_volatile(secret_key, SecretKey::default()) }
+    }
+
+    fn tweak_add(secret_key: &SecretKey, tweak: &[u8]) -> Result<SecretKey, Error> {
+        let mut child = SecretKey::parse_slice(tweak).map_err(|_| Error::InvalidChildKey)?;
+
+        child.tweak_add_assign(secret_key).map_err(|_| Error::InvalidChildKey)?;
+        Ok(child)
+    }
+
+    fn public_key(secret_key: &SecretKey) -> PublicKey {
+        PublicKey::from_secret_key(secret_key)
+    }
+
+    fn parse_public(bytes: &[u8]) -> Result<PublicKey, Error> {
+        PublicKey::parse_slice(bytes, None).map_err(|_| Error::InvalidPublicKey)
+    }
+
+    fn serialize_public(public_key: &PublicKey) -> [u8; 33] {
+        public_key.serialize_compressed()
+    }
+
+    fn serialize_public_uncompressed(public_key: &PublicKey) -> [u8; 65] {
+        public_key.serialize()
+    }
+
+    fn point_add(public_key: &PublicKey, tweak: &[u8]) -> Result<PublicKey, Error> {
+        let tweak = SecretKey::parse_slice(tweak).map_err(|_| Error::InvalidChildKey)?;
+        let mut child = public_key.clone();
+
+        child.tweak_add_assign(&tweak).map_err(|_| Error::InvalidChildKey)?;
+        Ok(child)
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt;
+
+use crate::bip32::InvalidKeyPolicy;
+use crate::protected::Protected;
+use crate::Error;
+
+#[cfg(feature = "libsecp256k1")]
+mod libsecp256k1;
+#[cfg(all(feature = "secp256k1-c", not(feature = "libsecp256k1")))]
+mod secp256k1_c;
+#[cfg(any(feature = "k256", feature = "nist256p1"))]
+mod rustcrypto;
+
+/// Elliptic curve operations that BIP32 and SLIP-0010 derivation is built on.
+///
+/// Secret keys and tweaks are passed as 32-byte big-endian scalars, public
+/// keys as 33-byte compressed SEC1 points. Implementors are unit marker
+/// types, so generic keys can derive `Clone`.
+pub trait Curve: Copy + Eq + fmt::Debug {
+    /// Name of the curve as used by SLIP-0010, shown by the `Debug` impls of
+    /// the generic keys.
+    const NAME: &'static str;
+
+    /// HMAC key used to derive the master key from a seed.
+    const SEED_KEY: &'static [u8];
+
+    /// How `child` and `derive` handle an invalid key. SLIP-0010 curves
+    /// rehash, secp256k1 reports an error for backwards compatibility.
+    const INVALID_KEY_POLICY: InvalidKeyPolicy;
+
+    /// Not required to be `PartialEq` or `Debug`, so that keys are only
+    /// compared and shown through the constant-time, redacted impls of the
+    /// extended key.
+    type SecretKey: Clone;
+    type PublicKey: Clone + fmt::Debug;
+
+    /// Parses a secret key, failing with `Error::InvalidSecretKey` if it is
+    /// not 32 bytes, zero or not below the curve order.
+    fn parse_secret(bytes: &[u8]) -> Result<Self::SecretKey, Error>;
+
+    fn serialize_secret(secret_key: &Self::SecretKey) -> Protected;
+
+    /// Overwrites the secret key in place so it no longer holds key material.
+    fn erase_secret(secret_key: &mut Self::SecretKey);
+
+    /// Computes `secret_key + tweak`, failing with `Error::InvalidChildKey`
+    /// if the tweak is not 32 bytes or not below the curve order, or if the
+    /// sum is zero.
+    fn tweak_add(secret_key: &Self::SecretKey, tweak: &[u8]) -> Result<Self::SecretKey, Error>;
+
+    fn public_key(secret_key: &Self::SecretKey) -> Self::PublicKey;
+
+    /// Parses a compressed public key, failing with `Error::InvalidPublicKey`
+    /// if it is not a point on the curve.
+    fn parse_public(bytes: &[u8]) -> Result<Self::PublicKey, Error>;
+
+    fn serialize_public(public_key: &Self::PublicKey) -> [u8; 33];
+
+    /// Uncompressed SEC1 encoding, `0x04` followed by both coordinates.
+    fn serialize_public_uncompressed(public_key: &Self::PublicKey) -> [u8; 65];
+
+    /// Computes `public_key + tweak * G`, failing with `Error::InvalidChildKey`
+    /// if the tweak is not 32 bytes or not below the curve order, or if the
+    /// sum is the point at infinity.
+    fn point_add(public_key: &Self::PublicKey, tweak: &[u8]) -> Result<Self::PublicKey, Error>;
+}
+
+/// The secp256k1 curve used by Bitcoin and Ethereum. It is backed by the
+/// first enabled feature out of `libsecp256k1`, `secp256k1-c` and `k256`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Secp256k1;
+
+/// The NIST P-256 curve, also known as secp256r1.
+#[cfg(feature = "nist256p1")]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct NistP256;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every backend must reject secrets and tweaks of the wrong length
+    /// the same way, whichever one is enabled.
+    fn rejects_wrong_lengths<C: Curve>() {
+        let secret_key = C::parse_secret(&[1; 32]).unwrap();
+        let public_key = C::public_key(&secret_key);
+
+        for len in [0, 1, 24, 31, 33, 64].iter() {
+            let bytes = vec![1; *len];
+
+            assert!(matches!(C::parse_secret(&bytes), Err(Error::InvalidSecretKey)), "{}-byte secret accepted", len);
+            assert!(matches!(C::tweak_add(&secret_key, &bytes), Err(Error::InvalidChildKey)), "{}-byte tweak accepted", len);
+            assert!(matches!(C::point_add(&public_key, &bytes), Err(Error::InvalidChildKey)), "{}-byte tweak accepted", len);
+        }
+    }
+
+    #[test]
+    fn secp256k1_wrong_lengths() {
+        rejects_wrong_lengths::<Secp256k1>();
+    }
+
+    #[cfg(feature = "nist256p1")]
+    #[test]
+    fn nist256p1_wrong_lengths() {
+        rejects_wrong_lengths::<NistP256>();
+    }
+}
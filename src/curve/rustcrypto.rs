@@ -0,0 +1,105 @@
+//! Backends built on the RustCrypto `elliptic-curve` crates, which share
+//! the same API for every curve.
+
+use std::convert::TryFrom;
+
+use zeroize::Zeroize;
+
+use super::Curve;
+use crate::bip32::InvalidKeyPolicy;
+use crate::protected::Protected;
+use crate::Error;
+
+macro_rules! rustcrypto_curve {
+    ($curve:ty, $krate:ident, $name:expr, $seed_key:expr, $policy:expr) => {
+        impl Curve for $curve {
+            const NAME: &'static str = $name;
+            const SEED_KEY: &'static [u8] = $seed_key;
+            const INVALID_KEY_POLICY: InvalidKeyPolicy = $policy;
+
+            type SecretKey = $krate::SecretKey;
+            type PublicKey = $krate::PublicKey;
+
+            fn parse_secret(bytes: &[u8]) -> Result<$krate::SecretKey, Error> {
+                // `from_slice` left-pads shorter slices, which other backends reject.
+                if bytes.len() != 32 {
+                    return Err(Error::InvalidSecretKey);
+                }
+
+                $krate::SecretKey::from_slice(bytes).map_err(|_| Error::InvalidSecretKey)
+            }
+
+            fn serialize_secret(secret_key: &$krate::SecretKey) -> Protected {
+                let mut repr = secret_key.to_bytes();
+                let secret = Protected::try_from(&repr[..]).expect("field elements are 32 bytes; qed");
+
+                repr[..].zeroize();
+                secret
+            }
+
+            fn erase_secret(secret_key: &mut $krate::SecretKey) {
+                // Dropping the old key zeroizes it.
+                let one = <$krate::Scalar as $krate::elliptic_curve::ff::Field>::ONE;
+
+                *secret_key = $krate::SecretKey::from($krate::NonZeroScalar::new(one).unwrap());
+            }
+
+            fn tweak_add(secret_key: &$krate::SecretKey, tweak: &[u8]) -> Result<$krate::SecretKey, Error> {
+                let tweak = Protected::<32>::try_from(tweak).map_err(|_| Error::InvalidChildKey)?;
+                let mut repr = $krate::FieldBytes::default();
+                repr.copy_from_slice(&tweak[..]);
+
+                let tweak: Option<$krate::Scalar> = <$krate::Scalar as $krate::elliptic_curve::ff::PrimeField>::from_repr(repr).into();
+                repr[..].zeroize();
+                let tweak = tweak.ok_or(Error::InvalidChildKey)?;
+                let child: Option<$krate::NonZeroScalar> = $krate::NonZeroScalar::new(tweak + *secret_key.to_nonzero_scalar()).into();
+
+                child.map($krate::SecretKey::from).ok_or(Error::InvalidChildKey)
+            }
+
+            fn public_key(secret_key: &$krate::SecretKey) -> $krate::PublicKey {
+                secret_key.public_key()
+            }
+
+            fn parse_public(bytes: &[u8]) -> Result<$krate::PublicKey, Error> {
+                $krate::PublicKey::from_sec1_bytes(bytes).map_err(|_| Error::InvalidPublicKey)
+            }
+
+            fn serialize_public(public_key: &$krate::PublicKey) -> [u8; 33] {
+                let point = $krate::elliptic_curve::sec1::ToEncodedPoint::to_encoded_point(public_key, true);
+                let mut serialized = [0u8; 33];
+
+                serialized.copy_from_slice(point.as_bytes());
+                serialized
+            }
+
+            fn serialize_public_uncompressed(public_key: &$krate::PublicKey) -> [u8; 65] {
+                let point = $krate::elliptic_curve::sec1::ToEncodedPoint::to_encoded_point(public_key, false);
+                let mut serialized = [0u8; 65];
+
+                serialized.copy_from_slice(point.as_bytes());
+                serialized
+            }
+
+            fn point_add(public_key: &$krate::PublicKey, tweak: &[u8]) -> Result<$krate::PublicKey, Error> {
+                let tweak = Protected::<32>::try_from(tweak).map_err(|_| Error::InvalidChildKey)?;
+                let mut repr = $krate::FieldBytes::default();
+                repr.copy_from_slice(&tweak[..]);
+
+                let tweak: Option<$krate::Scalar> = <$krate::Scalar as $krate::elliptic_curve::ff::PrimeField>::from_repr(repr).into();
+                repr[..].zeroize();
+                let tweak = tweak.ok_or(Error::InvalidChildKey)?;
+                let child = public_key.to_projective() + $krate::ProjectivePoint::GENERATOR * tweak;
+
+                $krate::PublicKey::from_affine($krate::elliptic_curve::group::Curve::to_affine(&child))
+                    .map_err(|_| Error::InvalidChildKey)
+            }
+        }
+    };
+}
+
+#[cfg(all(feature = "k256", not(any(feature = "libsecp256k1", feature = "secp256k1-c"))))]
+rustcrypto_curve!(super::Secp256k1, k256, "secp256k1", b"Bitcoin seed", InvalidKeyPolicy::Error);
+
+#[cfg(feature = "nist256p1")]
+rustcrypto_curve!(super::NistP256, p256, "nist256p1", b"Nist256p1 seed", InvalidKeyPolicy::Rehash);
@@ -0,0 +1,59 @@
+use std::convert::TryFrom;
+
+use secp256k1_c::{PublicKey, Scalar, SecretKey, SECP256K1};
+
+use super::{Curve, Secp256k1};
+use crate::bip32::InvalidKeyPolicy;
+use crate::protected::Protected;
+use crate::Error;
+
+impl Curve for Secp256k1 {
+    const NAME: &'static str = "secp256k1";
+    const SEED_KEY: &'static [u8] = b"Bitcoin seed";
+    const INVALID_KEY_POLICY: InvalidKeyPolicy = InvalidKeyPolicy::Error;
+
+    type SecretKey = SecretKey;
+    type PublicKey = PublicKey;
+
+    fn parse_secret(bytes: &[u8]) -> Result<SecretKey, Error> {
+        SecretKey::from_slice(bytes).map_err(|_| Error::InvalidSecretKey)
+    }
+
+    fn serialize_secret(secret_key: &SecretKey) -> Protected {
+        Protected::from(secret_key.secret_bytes())
+    }
+
+    fn erase_secret(secret_key: &mut SecretKey) {
+        secret_key.non_secure_erase();
+    }
+
+    fn tweak_add(secret_key: &SecretKey, tweak: &[u8]) -> Result<SecretKey, Error> {
+        secret_key.add_tweak(&scalar(tweak)?).map_err(|_| Error::InvalidChildKey)
+    }
+
+    fn public_key(secret_key: &SecretKey) -> PublicKey {
+        PublicKey::from_secret_key(SECP256K1, secret_key)
+    }
+
+    fn parse_public(bytes: &[u8]) -> Result<PublicKey, Error> {
+        PublicKey::from_slice(bytes).map_err(|_| Error::InvalidPublicKey)
+    }
+
+    fn serialize_public(public_key: &PublicKey) -> [u8; 33] {
+        public_key.serialize()
+    }
+
+    fn serialize_public_uncompressed(public_key: &PublicKey) -> [u8; 65] {
+        public_key.serialize_uncompressed()
+    }
+
+    fn point_add(public_key: &PublicKey, tweak: &[u8]) -> Result<PublicKey, Error> {
+        public_key.add_exp_tweak(SECP256K1, &scalar(tweak)?).map_err(|_| Error::InvalidChildKey)
+    }
+}
+
+fn scalar(bytes: &[u8]) -> Result<Scalar, Error> {
+    let repr = Protected::try_from(bytes).map_err(|_| Error::InvalidChildKey)?;
+
+    Scalar::from_be_bytes(*repr).map_err(|_| Error::InvalidChildKey)
+}
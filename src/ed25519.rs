@@ -0,0 +1,175 @@
+use std::fmt;
+
+use ed25519_dalek::{PublicKey, SecretKey};
+use sha2::Sha512;
+use hmac::{Hmac, Mac};
+
+use crate::bip32::{fingerprint, hex, hmac_result, Protected};
+use crate::bip44::{ChildNumber, IntoDerivationPath};
+use crate::seed;
+use crate::Error;
+
+/// SLIP-0010 extended private key on the ed25519 curve. Only hardened
+/// children can be derived on this curve.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ExtendedPrivKey {
+    secret_key: Protected,
+    chain_code: Protected,
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: ChildNumber,
+}
+
+/// Shows only public metadata, never the secret key or chain code.
+impl fmt::Debug for ExtendedPrivKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ExtendedPrivKey")
+            .field("depth", &self.depth)
+            .field("parent_fingerprint", &format_args!("{}", hex(&self.parent_fingerprint)))
+            .field("child_number", &format_args!("{}", self.child_number))
+            .field("fingerprint", &format_args!("{}", hex(&self.fingerprint())))
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExtendedPrivKey {
+    /// Attempts to derive an extended private key from a path. The seed
+    /// must be 128 to 512 bits long, as for BIP32, and the path must not
+    /// start from the master public key `M`.
+    pub fn derive<Path>(seed: &[u8], path: Path) -> Result<ExtendedPrivKey, Error>
+    where
+        Path: IntoDerivationPath,
+    {
+        seed::check_length(seed)?;
+
+        let path = path.into()?;
+        path.check_private()?;
+
+        let mut hmac: Hmac<Sha512> = Hmac::new_varkey(b"ed25519 seed").expect("seed is always correct; qed");
+        hmac.input(seed);
+
+        let result = hmac_result(hmac);
+        let (secret_key, chain_code) = result.halves();
+
+        let mut sk = ExtendedPrivKey {
+            secret_key: Protected::from(secret_key),
+            chain_code: Protected::from(chain_code),
+            depth: 0,
+            parent_fingerprint: [0; 4],
+            child_number: ChildNumber::non_hardened_from_u32(0),
+        };
+
+        for child in path.as_ref() {
+            sk = sk.child(*child)?;
+        }
+
+        Ok(sk)
+    }
+
+    /// The secret key, wiped from memory when the returned buffer is dropped.
+    pub fn secret(&self) -> Protected {
+        self.secret_key.clone()
+    }
+
+    /// The 32-byte ed25519 public key.
+    pub fn public_key(&self) -> [u8; 32] {
+        let secret_key = SecretKey::from_bytes(&self.secret_key[..]).expect("secret key is always 32 bytes; qed");
+
+        PublicKey::from(&secret_key).to_bytes()
+    }
+
+    /// Number of derivation steps from the master key.
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Fingerprint of the parent key, zeroed for the master key.
+    pub fn parent_fingerprint(&self) -> [u8; 4] {
+        self.parent_fingerprint
+    }
+
+    /// Child number this key was derived with, zero for the master key.
+    pub fn child_number(&self) -> ChildNumber {
+        self.child_number
+    }
+
+    /// Fingerprint of this key, the first four bytes of the HASH160 of
+    /// its public key prefixed with a zero byte.
+    pub fn fingerprint(&self) -> [u8; 4] {
+        let mut public_key = [0u8; 33];
+
+        public_key[1..].copy_from_slice(&self.public_key());
+        fingerprint(&public_key)
+    }
+
+    /// Attempts to derive a hardened child key.
+    pub fn child(&self, child: ChildNumber) -> Result<ExtendedPrivKey, Error> {
+        if child.is_normal() {
+            return Err(Error::NonHardenedChildNumber);
+        }
+
+        let depth = self.depth.checked_add(1).ok_or(Error::MaxDepthExceeded)?;
+
+        let mut hmac: Hmac<Sha512> = Hmac::new_varkey(&self.chain_code[..])
+            .map_err(|_| Error::InvalidChildNumber)?;
+
+        hmac.input(&[0]);
+        hmac.input(&self.secret_key[..]);
+        hmac.input(&child.to_bytes());
+
+        let result = hmac_result(hmac);
+        let (secret_key, chain_code) = result.halves();
+
+        Ok(ExtendedPrivKey {
+            secret_key: Protected::from(secret_key),
+            chain_code: Protected::from(chain_code),
+            depth,
+            parent_fingerprint: self.fingerprint(),
+            child_number: child,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slip10_vector_1() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+
+        let master = ExtendedPrivKey::derive(seed, "m").unwrap();
+
+        assert_eq!(&master.chain_code[..], b"\x90\x04\x6a\x93\xde\x53\x80\xa7\x2b\x5e\x45\x01\x07\x48\x56\x7d\x5e\xa0\x2b\xbf\x65\x22\xf9\x79\xe0\x5c\x0d\x8d\x8c\xa9\xff\xfb");
+        assert_eq!(&*master.secret(), b"\x2b\x4b\xe7\xf1\x9e\xe2\x7b\xbf\x30\xc6\x67\xb6\x42\xd5\xf4\xaa\x69\xfd\x16\x98\x72\xf8\xfc\x30\x59\xc0\x8e\xba\xe2\xeb\x19\xe7");
+        assert_eq!(&master.public_key(), b"\xa4\xb2\x85\x6b\xfe\xc5\x10\xab\xab\x89\x75\x3f\xac\x1a\xc0\xe1\x11\x23\x64\xe7\xd2\x50\x54\x59\x63\xf1\x35\xf2\xa3\x31\x88\xed");
+
+        let account = ExtendedPrivKey::derive(seed, "m/0'").unwrap();
+
+        assert_eq!(account, master.child(ChildNumber::hardened_from_u32(0)).unwrap());
+        assert_eq!(account.parent_fingerprint(), [0xdd, 0xeb, 0xc6, 0x75]);
+        assert_eq!(&account.chain_code[..], b"\x8b\x59\xaa\x11\x38\x0b\x62\x4e\x81\x50\x7a\x27\xfe\xdd\xa5\x9f\xea\x6d\x0b\x77\x9a\x77\x89\x18\xa2\xfd\x35\x90\xe1\x6e\x9c\x69");
+        assert_eq!(&*account.secret(), b"\x68\xe0\xfe\x46\xdf\xb6\x7e\x36\x8c\x75\x37\x9a\xce\xc5\x91\xda\xd1\x9d\xf3\xcd\xe2\x6e\x63\xb9\x3a\x8e\x70\x4f\x1d\xad\xe7\xa3");
+        assert_eq!(&account.public_key(), b"\x8c\x8a\x13\xdf\x77\xa2\x8f\x34\x45\x21\x3a\x0f\x43\x2f\xde\x64\x4a\xca\xa2\x15\xfc\x72\xdc\xdf\x30\x0d\x5e\xfa\xa8\x5d\x35\x0c");
+
+        let sk = ExtendedPrivKey::derive(seed, "m/0'/1'/2'/2'/1000000000'").unwrap();
+
+        assert_eq!(sk.depth(), 5);
+        assert_eq!(sk.parent_fingerprint(), [0xd6, 0x32, 0x2c, 0xcd]);
+        assert_eq!(sk.child_number(), ChildNumber::hardened_from_u32(1000000000));
+        assert_eq!(&sk.chain_code[..], b"\x68\x78\x99\x23\xa0\xca\xc2\xcd\x5a\x29\x17\x2a\x47\x5f\xe9\xe0\xfb\x14\xcd\x6a\xdb\x5a\xd9\x8a\x3f\xa7\x03\x33\xe7\xaf\xa2\x30");
+        assert_eq!(&*sk.secret(), b"\x8f\x94\xd3\x94\xa8\xe8\xfd\x6b\x1b\xc2\xf3\xf4\x9f\x5c\x47\xe3\x85\x28\x1d\x5c\x17\xe6\x53\x24\xb0\xf6\x24\x83\xe3\x7e\x87\x93");
+        assert_eq!(&sk.public_key(), b"\x3c\x24\xda\x04\x94\x51\x55\x5d\x51\xa7\x01\x4a\x37\x33\x7a\xa4\xe1\x2d\x41\xe4\x85\xab\xcc\xfa\x46\xb4\x7d\xfb\x2a\xf5\x4b\x7a");
+    }
+
+    #[test]
+    fn rejects_normal_children() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+
+        assert_eq!(ExtendedPrivKey::derive(seed, "m/44'/501'/0'/0"), Err(Error::NonHardenedChildNumber));
+
+        let master = ExtendedPrivKey::derive(seed, "m").unwrap();
+
+        assert_eq!(master.child(ChildNumber::non_hardened_from_u32(0)), Err(Error::NonHardenedChildNumber));
+    }
+}
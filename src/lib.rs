@@ -10,16 +10,107 @@
 //! let ext = ExtendedPrivKey::derive(seed, "m/44'/60'/0'/0/0").unwrap();
 //!
 //! // Byte array of the secp256k1 secret key that can be used with Bitcoin or Ethereum.
-//! assert_eq!(&ext.secret(), b"\x98\x84\xbf\x56\x24\xfa\xdd\x7f\xb2\x80\x4c\xfb\x0c\xb6\xf7\x1f\x28\x9e\x21\x1f\xcf\x0d\xe8\x36\xa3\x84\x17\x57\xda\xd9\x70\xd0");
+//! assert_eq!(&*ext.secret(), b"\x98\x84\xbf\x56\x24\xfa\xdd\x7f\xb2\x80\x4c\xfb\x0c\xb6\xf7\x1f\x28\x9e\x21\x1f\xcf\x0d\xe8\x36\xa3\x84\x17\x57\xda\xd9\x70\xd0");
 //! ```
 
+#[cfg(not(any(feature = "libsecp256k1", feature = "secp256k1-c", feature = "k256")))]
+compile_error!("one of the `libsecp256k1`, `secp256k1-c` or `k256` features must be enabled");
+
 pub mod bip44;
 pub mod bip32;
+#[cfg(any(feature = "ethereum", feature = "bitcoin"))]
+pub mod address;
+pub mod curve;
+pub mod protected;
+pub mod seed;
+pub mod template;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod tree;
+#[cfg(feature = "ed25519")]
+pub mod ed25519;
+#[cfg(feature = "nist256p1")]
+pub mod nist256p1;
+
+use std::fmt;
+
+use crate::bip44::PathError;
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Error {
-    Secp256k1(secp256k1::Error),
+    InvalidSecretKey,
+    InvalidPublicKey,
+    InvalidChildKey,
     InvalidChildNumber,
     InvalidDerivationPath,
+    InvalidPath(PathError),
     InvalidExtendedPrivKey,
+    InvalidExtendedPubKey,
+    InvalidChecksum,
+    UnknownVersion,
+    InvalidPrivateKeyPadding,
+    ZeroDepthParentFingerprint,
+    ZeroDepthChildNumber,
+    HardenedPublicDerivation,
+    NonHardenedChildNumber,
+    MaxDepthExceeded,
+    InvalidLength { expected: usize, actual: usize },
+    InvalidSeedLength(usize),
+    PublicPathRoot,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidSecretKey => f.write_str("invalid secret key"),
+            Error::InvalidPublicKey => f.write_str("invalid public key"),
+            Error::InvalidChildKey => f.write_str("derived child key is invalid"),
+            Error::InvalidChildNumber => f.write_str("invalid child number"),
+            Error::InvalidDerivationPath => f.write_str("derivation path doesn't have the expected structure"),
+            Error::InvalidPath(err) => write!(f, "invalid derivation path: {}", err),
+            Error::InvalidExtendedPrivKey => f.write_str("invalid extended private key"),
+            Error::InvalidExtendedPubKey => f.write_str("invalid extended public key"),
+            Error::InvalidChecksum => f.write_str("invalid Base58Check checksum"),
+            Error::UnknownVersion => f.write_str("unknown extended key version"),
+            Error::InvalidPrivateKeyPadding => f.write_str("private key is not prefixed with a zero byte"),
+            Error::ZeroDepthParentFingerprint => f.write_str("master key has a non-zero parent fingerprint"),
+            Error::ZeroDepthChildNumber => f.write_str("master key has a non-zero child number"),
+            Error::HardenedPublicDerivation => f.write_str("hardened children can't be derived from a public key"),
+            Error::NonHardenedChildNumber => f.write_str("curve only supports hardened derivation"),
+            Error::MaxDepthExceeded => f.write_str("maximum derivation depth of 255 exceeded"),
+            Error::InvalidLength { expected, actual } => write!(f, "expected {} bytes, got {}", expected, actual),
+            Error::InvalidSeedLength(len) => write!(f, "seed must be 16 to 64 bytes long, got {}", len),
+            Error::PublicPathRoot => f.write_str("paths starting with `M` can't be used to derive private keys"),
+        }
+    }
+}
+
+/// Key errors come from whichever curve backend is enabled and carry no
+/// more detail than the variant itself, so only path errors have a source.
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidPath(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn error_source() {
+        fn derive() -> Result<bip32::ExtendedPrivKey, Box<dyn std::error::Error>> {
+            Ok(bip32::ExtendedPrivKey::derive(&[0; 16], "m/0//1")?)
+        }
+
+        let err = derive().unwrap_err();
+        let path_err = err.source().unwrap().downcast_ref::<PathError>().unwrap();
+
+        assert_eq!(path_err.segment(), 2);
+        assert!(Error::InvalidSecretKey.source().is_none());
+    }
 }
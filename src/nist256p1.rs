@@ -0,0 +1,85 @@
+use crate::bip32::{GenericExtendedPrivKey, GenericExtendedPubKey};
+use crate::curve::NistP256;
+
+/// SLIP-0010 extended private key on the NIST P-256 (secp256r1) curve.
+pub type ExtendedPrivKey = GenericExtendedPrivKey<NistP256>;
+
+/// SLIP-0010 extended public key on the NIST P-256 (secp256r1) curve.
+pub type ExtendedPubKey = GenericExtendedPubKey<NistP256>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip44::ChildNumber;
+
+    #[test]
+    fn slip10_vector_1() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+
+        let master = ExtendedPrivKey::derive(seed, "m").unwrap();
+
+        assert_eq!(&master.chain_code[..], b"\xbe\xeb\x67\x2f\xe4\x62\x16\x73\xf7\x22\xf3\x85\x29\xc0\x73\x92\xfe\xca\xa6\x10\x15\xc8\x0c\x34\xf2\x9c\xe8\xb4\x1b\x3c\xb6\xea");
+        assert_eq!(&*master.secret(), b"\x61\x20\x91\xaa\xa1\x2e\x22\xdd\x2a\xbe\xf6\x64\xf8\xa0\x1a\x82\xca\xe9\x9a\xd7\x44\x1b\x7e\xf8\x11\x04\x24\x91\x5c\x26\x8b\xc2");
+        assert_eq!(&master.public_key()[..], &b"\x02\x66\x87\x4d\xc6\xad\xe4\x7b\x3e\xcd\x09\x67\x45\xca\x09\xbc\xd2\x96\x38\xdd\x52\xc2\xc1\x21\x17\xb1\x1e\xd3\xe4\x58\xcf\xa9\xe8"[..]);
+
+        let account = ExtendedPrivKey::derive(seed, "m/0'").unwrap();
+
+        assert_eq!(account, master.child(ChildNumber::hardened_from_u32(0)).unwrap());
+        assert_eq!(account.parent_fingerprint(), [0xbe, 0x61, 0x05, 0xb5]);
+        assert_eq!(&account.chain_code[..], b"\x34\x60\xce\xa5\x3e\x6a\x6b\xb5\xfb\x39\x1e\xee\xf3\x23\x7f\xfd\x87\x24\xbf\x0a\x40\xe9\x49\x43\xc9\x8b\x83\x82\x53\x42\xee\x11");
+        assert_eq!(&*account.secret(), b"\x69\x39\x69\x43\x69\x11\x4c\x67\x91\x7a\x18\x2c\x59\xdd\xb8\xca\xfc\x30\x04\xe6\x3c\xa5\xd3\xb8\x44\x03\xba\x86\x13\xde\xbc\x0c");
+        assert_eq!(&account.public_key()[..], &b"\x03\x84\x61\x0f\x5e\xcf\xfe\x8f\xda\x08\x93\x63\xa4\x1f\x56\xa5\xc7\xff\xc1\xd8\x1b\x59\xa6\x12\xd0\xd6\x49\xb2\xd2\x23\x55\x59\x0c"[..]);
+
+        let sk = ExtendedPrivKey::derive(seed, "m/0'/1/2'/2/1000000000").unwrap();
+
+        assert_eq!(sk.depth(), 5);
+        assert_eq!(sk.parent_fingerprint(), [0x8b, 0x2b, 0x5c, 0x4b]);
+        assert_eq!(&sk.chain_code[..], b"\xb9\xb7\xb8\x2d\x32\x6b\xb9\xcb\x5b\x5b\x12\x10\x66\xfe\xea\x4e\xb9\x3d\x52\x41\x10\x3c\x9e\x7a\x18\xaa\xd4\x0f\x1d\xde\x80\x59");
+        assert_eq!(&*sk.secret(), b"\x21\xc4\xf2\x69\xef\x0a\x5f\xd1\xba\xdf\x47\xee\xac\xeb\xee\xaa\x3d\xe2\x2e\xb8\xe5\xb0\xad\xcd\x0f\x27\xdd\x99\xd3\x4d\x01\x19");
+        assert_eq!(&sk.public_key()[..], &b"\x02\x21\x6c\xd2\x6d\x31\x14\x7f\x72\x42\x7a\x45\x3c\x44\x3e\xd2\xcd\xe8\xa1\xe5\x3c\x9c\xc4\x4e\x5d\xdf\x73\x97\x25\x41\x3f\xe3\xf4"[..]);
+    }
+
+    #[test]
+    fn public_child_matches_private_child() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+
+        let account = ExtendedPrivKey::derive(seed, "m/0'").unwrap();
+        let xpub = ExtendedPubKey::from_private(&account).child(ChildNumber::non_hardened_from_u32(1)).unwrap();
+
+        assert_eq!(xpub, ExtendedPubKey::from_private(&ExtendedPrivKey::derive(seed, "m/0'/1").unwrap()));
+    }
+
+    #[test]
+    fn debug_names_curve() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+        let master = ExtendedPrivKey::derive(seed, "m").unwrap();
+
+        assert!(format!("{:?}", master).starts_with("GenericExtendedPrivKey { curve: nist256p1,"));
+        assert!(format!("{:?}", ExtendedPubKey::from_private(&master))
+            .starts_with("GenericExtendedPubKey { curve: nist256p1, version: Mainnet, depth: 0, parent_fingerprint: 00000000, child_number: 0, \
+                          public_key: 0266874dc6ade47b3ecd096745ca09bcd29638dd52c2c12117b11ed3e458cfa9e8,"));
+    }
+
+    #[test]
+    fn slip10_derivation_retry() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+
+        let sk = ExtendedPrivKey::derive(seed, "m/28578'/33941").unwrap();
+
+        assert_eq!(sk.parent_fingerprint(), [0x3e, 0x2b, 0x7b, 0xc6]);
+        assert_eq!(&sk.chain_code[..], b"\x9e\x87\xfe\x95\x03\x1f\x14\x73\x67\x74\xcd\x82\xf2\x5f\xd8\x85\x06\x5c\xb7\xc3\x58\xc1\xed\xf8\x13\xc7\x2a\xf5\x35\xe8\x30\x71");
+        assert_eq!(&*sk.secret(), b"\x09\x21\x54\xee\xd4\xaf\x83\xe0\x78\xff\x9b\x84\x32\x20\x15\xae\xfe\x57\x69\xe3\x12\x70\xf6\x2c\x3f\x66\xc3\x38\x88\x33\x5f\x3a");
+        assert_eq!(&sk.public_key()[..], &b"\x02\x35\xbf\xee\x61\x4c\x0d\x5b\x2c\xae\x26\x00\x00\xbb\x1d\x0d\x84\xb2\x70\x09\x9a\xd7\x90\x02\x2c\x1a\xe0\xb2\xe7\x82\xef\xe1\x20"[..]);
+    }
+
+    #[test]
+    fn slip10_seed_retry() {
+        let seed = b"\xa7\x30\x5b\xc8\xdf\x8d\x09\x51\xf0\xcb\x22\x4c\x0e\x95\xd7\x70\x7c\xbd\xf2\xc6\xce\x7e\x8d\x48\x1f\xec\x69\xc7\xff\x5e\x94\x46";
+
+        let master = ExtendedPrivKey::derive(seed, "m").unwrap();
+
+        assert_eq!(&master.chain_code[..], b"\x77\x62\xf9\x72\x9f\xed\x06\x12\x1f\xd1\x3f\x32\x68\x84\xc8\x2f\x59\xaa\x95\xc5\x7a\xc4\x92\xce\x8c\x96\x54\xe6\x0e\xfd\x13\x0c");
+        assert_eq!(&*master.secret(), b"\x3b\x8c\x18\x46\x9a\x46\x34\x51\x7d\x6d\x0b\x65\x44\x8f\x8e\x6c\x62\x09\x1b\x45\x54\x0a\x17\x43\xc5\x84\x6b\xe5\x5d\x47\xd8\x8f");
+        assert_eq!(&master.public_key()[..], &b"\x03\x83\x61\x9f\xad\xcd\xe3\x10\x63\xd8\xc5\xcb\x00\xdb\xfe\x17\x13\xf3\xe6\xfa\x16\x9d\x85\x41\xa7\x98\x75\x2a\x1c\x1c\xa0\xcb\x20"[..]);
+    }
+}
@@ -0,0 +1,75 @@
+//! Parallel batch derivation on the rayon thread pool, enabled by the
+//! `rayon` feature.
+//!
+//! Results are returned in the order of the requested children and are
+//! identical to those of the sequential `children` methods, including which
+//! error is reported when several children fail.
+
+use rayon::prelude::*;
+
+use crate::bip32::{GenericExtendedPrivKey, GenericExtendedPubKey};
+use crate::bip44::ChildNumber;
+use crate::curve::Curve;
+use crate::Error;
+
+impl<C: Curve> GenericExtendedPrivKey<C>
+where
+    GenericExtendedPrivKey<C>: Send + Sync,
+{
+    /// Derives a child for each of `children` in parallel, the same as
+    /// `children`.
+    pub fn par_children(&self, children: &[ChildNumber]) -> Result<Vec<GenericExtendedPrivKey<C>>, Error> {
+        first_error(children.par_iter().map(|child| self.child(*child)).collect())
+    }
+}
+
+impl<C: Curve> GenericExtendedPubKey<C>
+where
+    GenericExtendedPubKey<C>: Send + Sync,
+{
+    /// Derives a child for each of `children` in parallel, the same as
+    /// `children`.
+    pub fn par_children(&self, children: &[ChildNumber]) -> Result<Vec<GenericExtendedPubKey<C>>, Error> {
+        first_error(children.par_iter().map(|child| self.child(*child)).collect())
+    }
+}
+
+/// Collecting into a `Result` directly would report whichever error a
+/// thread hit first, so errors are resolved in order afterwards.
+fn first_error<Key>(results: Vec<Result<Key, Error>>) -> Result<Vec<Key>, Error> {
+    results.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip32::{ExtendedPrivKey, ExtendedPubKey};
+
+    const SEED: &[u8] = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+
+    #[test]
+    fn matches_sequential() {
+        let account = ExtendedPrivKey::derive(SEED, "m/44'/60'/0'/0").unwrap();
+        let xpub = ExtendedPubKey::from_private(&account);
+        let children: Vec<ChildNumber> = (0..200).map(ChildNumber::non_hardened_from_u32).collect();
+
+        assert_eq!(account.par_children(&children), account.children(&children));
+        assert_eq!(xpub.par_children(&children), xpub.children(&children));
+
+        let hardened: Vec<ChildNumber> = (0..50).map(ChildNumber::hardened_from_u32).collect();
+
+        assert_eq!(account.par_children(&hardened), account.children(&hardened));
+        assert_eq!(account.par_children(&[]), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn reports_first_error() {
+        let xpub = ExtendedPubKey::from_private(&ExtendedPrivKey::derive(SEED, "m/0'").unwrap());
+        let mut children: Vec<ChildNumber> = (0..100).map(ChildNumber::non_hardened_from_u32).collect();
+
+        children[40] = ChildNumber::hardened_from_u32(40);
+
+        assert_eq!(xpub.par_children(&children), Err(Error::HardenedPublicDerivation));
+        assert_eq!(xpub.children(&children), Err(Error::HardenedPublicDerivation));
+    }
+}
@@ -0,0 +1,281 @@
+//! Fixed-size buffers for secret data.
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+use crate::Error;
+
+/// Secret bytes that are wiped from memory when dropped and compared in
+/// constant time.
+///
+/// The bytes live on the heap so that moving the buffer around doesn't leave
+/// copies behind. With the `mlock` feature their pages are also locked into
+/// memory to keep them out of swap. Locking is best effort: if it fails,
+/// for example because `RLIMIT_MEMLOCK` is exhausted, the buffer is still
+/// usable, only swappable.
+pub struct Protected<const N: usize = 32> {
+    data: Box<[u8; N]>,
+    #[cfg(feature = "mlock")]
+    locked: bool,
+}
+
+impl<const N: usize> Protected<N> {
+    fn new(data: Box<[u8; N]>) -> Protected<N> {
+        Protected {
+            #[cfg(feature = "mlock")]
+            locked: mlock::lock(&data[..]),
+            data,
+        }
+    }
+}
+
+/// A zeroed buffer.
+impl<const N: usize> Default for Protected<N> {
+    fn default() -> Protected<N> {
+        Protected::new(Box::new([0u8; N]))
+    }
+}
+
+/// Takes the bytes and wipes the array they were passed in.
+impl<const N: usize> From<[u8; N]> for Protected<N> {
+    fn from(mut data: [u8; N]) -> Protected<N> {
+        let protected = Protected::from(&data);
+
+        data.zeroize();
+        protected
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for Protected<N> {
+    fn from(data: &[u8; N]) -> Protected<N> {
+        let mut protected = Protected::default();
+
+        protected.copy_from_slice(data);
+        protected
+    }
+}
+
+/// Fails with `Error::InvalidLength` unless the slice holds exactly `N` bytes.
+impl<const N: usize> TryFrom<&[u8]> for Protected<N> {
+    type Error = Error;
+
+    fn try_from(data: &[u8]) -> Result<Protected<N>, Error> {
+        if data.len() != N {
+            return Err(Error::InvalidLength { expected: N, actual: data.len() });
+        }
+
+        let mut protected = Protected::default();
+
+        protected.copy_from_slice(data);
+        Ok(protected)
+    }
+}
+
+impl Protected<64> {
+    /// The two 32-byte halves, such as IL and IR of a BIP32 HMAC output.
+    pub(crate) fn halves(&self) -> (&[u8; 32], &[u8; 32]) {
+        let (left, right) = self.split_at(32);
+
+        (
+            left.try_into().expect("half of 64 bytes is 32 bytes; qed"),
+            right.try_into().expect("half of 64 bytes is 32 bytes; qed"),
+        )
+    }
+}
+
+impl<const N: usize> Deref for Protected<N> {
+    type Target = [u8; N];
+
+    fn deref(&self) -> &[u8; N] {
+        &self.data
+    }
+}
+
+impl<const N: usize> DerefMut for Protected<N> {
+    fn deref_mut(&mut self) -> &mut [u8; N] {
+        &mut self.data
+    }
+}
+
+impl<const N: usize> Clone for Protected<N> {
+    fn clone(&self) -> Protected<N> {
+        Protected::from(&*self.data)
+    }
+}
+
+/// Compares in constant time.
+impl<const N: usize> PartialEq for Protected<N> {
+    fn eq(&self, other: &Protected<N>) -> bool {
+        self.data.ct_eq(&*other.data).into()
+    }
+}
+
+impl<const N: usize> Eq for Protected<N> {}
+
+impl<const N: usize> fmt::Debug for Protected<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Protected")
+    }
+}
+
+impl<const N: usize> Drop for Protected<N> {
+    fn drop(&mut self) {
+        self.data.zeroize();
+
+        #[cfg(feature = "mlock")]
+        {
+            if self.locked {
+                mlock::unlock(&self.data[..]);
+            }
+        }
+    }
+}
+
+/// Page locking shared by all buffers. Small buffers share pages and
+/// `munlock` isn't reference counted by the OS, so each page is unlocked
+/// only once the last buffer on it is dropped.
+#[cfg(feature = "mlock")]
+mod mlock {
+    use std::collections::HashMap;
+    use std::sync::{Mutex, OnceLock};
+
+    fn pages() -> &'static Mutex<HashMap<usize, usize>> {
+        static PAGES: OnceLock<Mutex<HashMap<usize, usize>>> = OnceLock::new();
+
+        PAGES.get_or_init(Default::default)
+    }
+
+    /// Addresses of the pages spanned by `data`.
+    fn span(data: &[u8]) -> impl Iterator<Item = usize> {
+        let size = region::page::size();
+        let start = region::page::floor(data.as_ptr()) as usize;
+        let end = data.as_ptr() as usize + data.len();
+
+        (start..end).step_by(size)
+    }
+
+    /// Locks the pages spanned by `data`, returning whether all of them are
+    /// locked.
+    pub(super) fn lock(data: &[u8]) -> bool {
+        if data.is_empty() {
+            return false;
+        }
+
+        let mut pages = pages().lock().unwrap_or_else(|err| err.into_inner());
+        let mut locked = Vec::new();
+
+        for page in span(data) {
+            if !pages.contains_key(&page) {
+                match region::lock(page as *const u8, 1) {
+                    Ok(guard) => std::mem::forget(guard),
+                    Err(_) => {
+                        // Leave no page locked on behalf of this buffer.
+                        for page in locked {
+                            release(&mut pages, page);
+                        }
+
+                        return false;
+                    }
+                }
+            }
+
+            *pages.entry(page).or_insert(0) += 1;
+            locked.push(page);
+        }
+
+        true
+    }
+
+    pub(super) fn unlock(data: &[u8]) {
+        let mut pages = pages().lock().unwrap_or_else(|err| err.into_inner());
+
+        for page in span(data) {
+            release(&mut pages, page);
+        }
+    }
+
+    fn release(pages: &mut HashMap<usize, usize>, page: usize) {
+        if let Some(count) = pages.get_mut(&page) {
+            *count -= 1;
+
+            if *count == 0 {
+                pages.remove(&page);
+                let _ = region::unlock(page as *const u8, 1);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    pub(super) fn is_locked(data: &[u8]) -> bool {
+        let pages = pages().lock().unwrap_or_else(|err| err.into_inner());
+
+        span(data).all(|page| pages.contains_key(&page))
+    }
+
+    #[cfg(test)]
+    pub(super) fn share_page(first: &[u8], second: &[u8]) -> bool {
+        span(first).any(|page| span(second).any(|other| other == page))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn length_mismatch() {
+        assert_eq!(Protected::<32>::try_from(&[1u8; 31][..]), Err(Error::InvalidLength { expected: 32, actual: 31 }));
+        assert_eq!(Protected::<64>::try_from(&[1u8; 65][..]), Err(Error::InvalidLength { expected: 64, actual: 65 }));
+        assert_eq!(*Protected::<64>::try_from(&[1u8; 64][..]).unwrap(), [1u8; 64]);
+    }
+
+    #[test]
+    fn halves() {
+        let mut data = [0u8; 64];
+        data[32..].copy_from_slice(&[1u8; 32]);
+
+        let protected = Protected::from(data);
+
+        assert_eq!(protected.halves(), (&[0u8; 32], &[1u8; 32]));
+    }
+
+    #[test]
+    fn equality() {
+        let protected = Protected::from([7u8; 32]);
+        let mut other = protected.clone();
+
+        assert_eq!(protected, other);
+
+        other[31] = 8;
+        assert_ne!(protected, other);
+        assert_eq!(format!("{:?}", other), "Protected");
+    }
+
+    #[cfg(feature = "mlock")]
+    #[test]
+    fn unlocks_shared_pages_last() {
+        let first = Protected::from([1u8; 32]);
+
+        // Locking can fail in a constrained sandbox, which is allowed.
+        if !first.locked {
+            return;
+        }
+
+        let second = first.clone();
+        assert!(second.locked);
+
+        // Small boxes allocated back to back nearly always share a page, but
+        // the allocator doesn't promise it, and without a shared page this
+        // test wouldn't exercise the reference counting.
+        if !mlock::share_page(&first[..], &second[..]) {
+            return;
+        }
+
+        drop(first);
+        assert!(mlock::is_locked(&second[..]));
+    }
+}
@@ -0,0 +1,135 @@
+//! Seeds that master keys are derived from.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Deref;
+
+use crate::protected::Protected;
+use crate::Error;
+
+/// Shortest seed BIP32 allows, 128 bits.
+pub const MIN_SEED_LEN: usize = 16;
+
+/// Longest seed BIP32 allows, 512 bits.
+pub const MAX_SEED_LEN: usize = 64;
+
+/// A seed of 128 to 512 bits, wiped from memory when dropped.
+///
+/// `derive` takes any byte slice and checks its length itself, so a `Seed`
+/// is passed to it as `&seed`. Constructing one upfront moves the check to
+/// where the seed enters the program.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Seed {
+    data: Protected<MAX_SEED_LEN>,
+    len: usize,
+}
+
+impl Seed {
+    /// Copies the seed, failing with `Error::InvalidSeedLength` unless it is
+    /// between 16 and 64 bytes long.
+    pub fn new(seed: &[u8]) -> Result<Seed, Error> {
+        check_length(seed)?;
+
+        let mut data = Protected::default();
+        data[..seed.len()].copy_from_slice(seed);
+
+        Ok(Seed { data, len: seed.len() })
+    }
+}
+
+pub(crate) fn check_length(seed: &[u8]) -> Result<(), Error> {
+    if seed.len() < MIN_SEED_LEN || seed.len() > MAX_SEED_LEN {
+        return Err(Error::InvalidSeedLength(seed.len()));
+    }
+
+    Ok(())
+}
+
+impl TryFrom<&[u8]> for Seed {
+    type Error = Error;
+
+    fn try_from(seed: &[u8]) -> Result<Seed, Error> {
+        Seed::new(seed)
+    }
+}
+
+/// The 512-bit seed that BIP39 mnemonics produce, as returned by
+/// `Mnemonic::to_seed` of the `bip39` crate. The array is wiped.
+impl From<[u8; 64]> for Seed {
+    fn from(seed: [u8; 64]) -> Seed {
+        Seed { data: Protected::from(seed), len: 64 }
+    }
+}
+
+#[cfg(feature = "bip39")]
+impl From<&bip39::Seed> for Seed {
+    fn from(seed: &bip39::Seed) -> Seed {
+        Seed::new(seed.as_bytes()).expect("BIP39 seeds are 64 bytes; qed")
+    }
+}
+
+#[cfg(feature = "bip39")]
+impl From<bip39::Seed> for Seed {
+    fn from(seed: bip39::Seed) -> Seed {
+        Seed::from(&seed)
+    }
+}
+
+impl Deref for Seed {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+impl AsRef<[u8]> for Seed {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl fmt::Debug for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Seed({} bits)", self.len * 8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip32::ExtendedPrivKey;
+
+    #[test]
+    fn length_bounds() {
+        assert_eq!(Seed::new(&[]), Err(Error::InvalidSeedLength(0)));
+        assert_eq!(Seed::new(&[1; 15]), Err(Error::InvalidSeedLength(15)));
+        assert_eq!(Seed::new(&[1; 65]), Err(Error::InvalidSeedLength(65)));
+        assert_eq!(&*Seed::new(&[1; 16]).unwrap(), &[1; 16]);
+        assert_eq!(&*Seed::try_from(&[2; 64][..]).unwrap(), &[2; 64][..]);
+        assert_eq!(format!("{:?}", Seed::from([3; 64])), "Seed(512 bits)");
+    }
+
+    #[test]
+    fn derive_rejects_bad_lengths() {
+        assert_eq!(ExtendedPrivKey::derive(&[], "m/0'"), Err(Error::InvalidSeedLength(0)));
+        assert_eq!(ExtendedPrivKey::derive(&[1], "m"), Err(Error::InvalidSeedLength(1)));
+        assert_eq!(ExtendedPrivKey::derive(&[1; 100], "m"), Err(Error::InvalidSeedLength(100)));
+
+        let seed = Seed::new(&[1; 32]).unwrap();
+        assert_eq!(ExtendedPrivKey::derive(&seed, "m/0'"), ExtendedPrivKey::derive(&[1; 32], "m/0'"));
+    }
+
+    #[cfg(feature = "bip39")]
+    #[test]
+    fn from_bip39() {
+        use bip39::{Language, Mnemonic};
+
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let mnemonic = Mnemonic::from_phrase(phrase, Language::English).unwrap();
+        let bip39_seed = bip39::Seed::new(&mnemonic, "");
+
+        assert_eq!(&*Seed::from(&bip39_seed), bip39_seed.as_bytes());
+        assert_eq!(Seed::from(&bip39_seed), Seed::from(bip39_seed));
+    }
+}
@@ -0,0 +1,382 @@
+//! Path templates describing many derivation paths at once, such as
+//! `m/84'/0'/0'/<0;1>/*` or `m/44'/60'/0'/0/[0-999]`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::bip32::GenericExtendedPrivKey;
+use crate::bip44::{parse_child, split_root, ChildNumber, DerivationPath, PathError, PathErrorKind, PathRoot};
+use crate::curve::Curve;
+use crate::Error;
+
+const MAX_INDEX: u32 = (1 << 31) - 1;
+
+/// A single level of a `PathTemplate`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Segment {
+    /// A plain child number, `0` or `44'`.
+    Child(ChildNumber),
+    /// One of several child numbers, `<0;1>`.
+    Alternatives(Vec<ChildNumber>),
+    /// An inclusive range of indices, `[0-999]` or `[0-9]'`.
+    Range { start: u32, end: u32, hardened: bool },
+    /// Every index, `*` or `*'`.
+    Wildcard { hardened: bool },
+}
+
+impl Segment {
+    fn len(&self) -> u32 {
+        match self {
+            Segment::Child(_) => 1,
+            Segment::Alternatives(children) => children.len() as u32,
+            Segment::Range { start, end, .. } => end - start + 1,
+            Segment::Wildcard { .. } => MAX_INDEX + 1,
+        }
+    }
+
+    fn get(&self, i: u32) -> ChildNumber {
+        let (index, hardened) = match self {
+            Segment::Child(child) => return *child,
+            Segment::Alternatives(children) => return children[i as usize],
+            Segment::Range { start, hardened, .. } => (start + i, *hardened),
+            Segment::Wildcard { hardened } => (i, *hardened),
+        };
+
+        if hardened {
+            ChildNumber::hardened_from_u32(index)
+        } else {
+            ChildNumber::non_hardened_from_u32(index)
+        }
+    }
+}
+
+/// A derivation path whose levels may be alternatives, ranges or wildcards,
+/// expanding to every matching `DerivationPath`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PathTemplate {
+    root: PathRoot,
+    segments: Vec<Segment>,
+}
+
+impl PathTemplate {
+    pub fn root(&self) -> PathRoot {
+        self.root
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Iterates over every path matching the template, the last level
+    /// varying fastest.
+    pub fn paths(&self) -> Paths<'_> {
+        Paths {
+            template: self,
+            indices: vec![0; self.segments.len()],
+            done: false,
+        }
+    }
+}
+
+impl From<DerivationPath> for PathTemplate {
+    fn from(path: DerivationPath) -> PathTemplate {
+        PathTemplate {
+            root: path.root(),
+            segments: path.into_iter().map(Segment::Child).collect(),
+        }
+    }
+}
+
+/// Parses templates using `DerivationPath` syntax, where any level may
+/// also be `<a;b;...>`, `[a-b]` or `*`. Ranges and wildcards take a
+/// hardened marker after the closing bracket or star, alternatives take
+/// one on each child.
+impl FromStr for PathTemplate {
+    type Err = Error;
+
+    fn from_str(template: &str) -> Result<PathTemplate, Error> {
+        let (root, segments) = split_root(template);
+
+        let segments = segments
+            .map(|(segment, text)| {
+                parse_segment(text).map_err(|kind| Error::InvalidPath(PathError::new(segment, text, kind)))
+            })
+            .collect::<Result<Vec<Segment>, Error>>()?;
+
+        Ok(PathTemplate { root, segments })
+    }
+}
+
+fn parse_segment(text: &str) -> Result<Segment, PathErrorKind> {
+    if let Some(alternatives) = text.strip_prefix('<') {
+        let alternatives = alternatives.strip_suffix('>').ok_or(PathErrorKind::InvalidTemplate)?;
+
+        return alternatives.split(';').map(parse_child).collect::<Result<_, _>>().map(Segment::Alternatives);
+    }
+
+    if let Some(range) = text.strip_prefix('[') {
+        let (range, marker) = range.split_once(']').ok_or(PathErrorKind::InvalidTemplate)?;
+        let (start, end) = range.split_once('-').ok_or(PathErrorKind::InvalidTemplate)?;
+        let (start, end) = (parse_child(start)?, parse_child(end)?);
+
+        if start.is_hardened() || end.is_hardened() || start.index() > end.index() {
+            return Err(PathErrorKind::InvalidTemplate);
+        }
+
+        let hardened = parse_marker(marker)?;
+
+        return Ok(Segment::Range { start: start.index(), end: end.index(), hardened });
+    }
+
+    if let Some(marker) = text.strip_prefix('*') {
+        return Ok(Segment::Wildcard { hardened: parse_marker(marker)? });
+    }
+
+    parse_child(text).map(Segment::Child)
+}
+
+fn parse_marker(marker: &str) -> Result<bool, PathErrorKind> {
+    match marker {
+        "" => Ok(false),
+        "'" | "h" | "H" => Ok(true),
+        _ => Err(PathErrorKind::BadHardenedMarker),
+    }
+}
+
+/// Formats like `DerivationPath`, using `h` markers when the alternate
+/// flag is set (`{:#}`).
+impl fmt::Display for PathTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let marker = if f.alternate() { "h" } else { "'" };
+        let mut separator = match self.root {
+            PathRoot::Private => { f.write_str("m")?; "/" },
+            PathRoot::Public => { f.write_str("M")?; "/" },
+            PathRoot::Relative => "",
+        };
+
+        for segment in self.segments.iter() {
+            f.write_str(separator)?;
+
+            match segment {
+                Segment::Child(child) => write_child(f, child)?,
+                Segment::Alternatives(children) => {
+                    f.write_str("<")?;
+
+                    for (i, child) in children.iter().enumerate() {
+                        if i > 0 {
+                            f.write_str(";")?;
+                        }
+
+                        write_child(f, child)?;
+                    }
+
+                    f.write_str(">")?;
+                }
+                Segment::Range { start, end, hardened } => {
+                    write!(f, "[{}-{}]{}", start, end, if *hardened { marker } else { "" })?;
+                }
+                Segment::Wildcard { hardened } => {
+                    write!(f, "*{}", if *hardened { marker } else { "" })?;
+                }
+            }
+
+            separator = "/";
+        }
+
+        Ok(())
+    }
+}
+
+fn write_child(f: &mut fmt::Formatter, child: &ChildNumber) -> fmt::Result {
+    if f.alternate() {
+        write!(f, "{:#}", child)
+    } else {
+        write!(f, "{}", child)
+    }
+}
+
+/// Iterator over the paths matching a `PathTemplate`, see `PathTemplate::paths`.
+#[derive(Clone, Debug)]
+pub struct Paths<'a> {
+    template: &'a PathTemplate,
+    indices: Vec<u32>,
+    done: bool,
+}
+
+impl Iterator for Paths<'_> {
+    type Item = DerivationPath;
+
+    fn next(&mut self) -> Option<DerivationPath> {
+        if self.done {
+            return None;
+        }
+
+        let segments = &self.template.segments;
+        let path = segments.iter().zip(self.indices.iter()).map(|(segment, i)| segment.get(*i)).collect();
+
+        // Advance like an odometer, finishing once the first level wraps.
+        self.done = true;
+
+        for (segment, i) in segments.iter().zip(self.indices.iter_mut()).rev() {
+            *i += 1;
+
+            if *i < segment.len() {
+                self.done = false;
+                break;
+            }
+
+            *i = 0;
+        }
+
+        Some(DerivationPath::new(self.template.root, path))
+    }
+}
+
+impl<C: Curve> GenericExtendedPrivKey<C> {
+    /// Derives the key of every path matching `template` from a seed.
+    ///
+    /// Keys are derived lazily in the order of `PathTemplate::paths`. Each
+    /// intermediate key is derived once and reused for as long as following
+    /// paths share it, so the common prefix costs a single derivation and
+    /// every further path only walks the levels that changed.
+    ///
+    /// Fails with `Error::PublicPathRoot` if the template starts from `M`.
+    pub fn derive_batch<'a>(seed: &[u8], template: &'a PathTemplate) -> Result<DerivedKeys<'a, C>, Error> {
+        if template.root == PathRoot::Public {
+            return Err(Error::PublicPathRoot);
+        }
+
+        let master = GenericExtendedPrivKey::derive(seed, DerivationPath::default())?;
+
+        Ok(DerivedKeys {
+            paths: template.paths(),
+            keys: vec![master],
+            prefix: Vec::new(),
+        })
+    }
+}
+
+/// Iterator over the keys of a `PathTemplate`, see
+/// `GenericExtendedPrivKey::derive_batch`.
+pub struct DerivedKeys<'a, C: Curve> {
+    paths: Paths<'a>,
+    /// `keys[i]` is the key at the first `i` children of `prefix`.
+    keys: Vec<GenericExtendedPrivKey<C>>,
+    prefix: Vec<ChildNumber>,
+}
+
+impl<C: Curve> Iterator for DerivedKeys<'_, C> {
+    type Item = Result<(DerivationPath, GenericExtendedPrivKey<C>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let path = self.paths.next()?;
+        let shared = self.prefix.iter().zip(path.iter()).take_while(|(a, b)| a == b).count();
+
+        self.prefix.truncate(shared);
+        self.keys.truncate(shared + 1);
+
+        for child in path.as_ref()[shared..].iter() {
+            match self.keys[self.keys.len() - 1].child(*child) {
+                Ok(key) => self.keys.push(key),
+                Err(err) => return Some(Err(err)),
+            }
+
+            self.prefix.push(*child);
+        }
+
+        let key = self.keys[self.keys.len() - 1].clone();
+
+        Some(Ok((path, key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip32::ExtendedPrivKey;
+
+    fn paths(template: &str) -> Vec<String> {
+        template.parse::<PathTemplate>().unwrap().paths().map(|path| path.to_string()).collect()
+    }
+
+    #[test]
+    fn expand() {
+        assert_eq!(paths("m/84'/0'/0'/<0;1>/[0-2]"), [
+            "m/84'/0'/0'/0/0", "m/84'/0'/0'/0/1", "m/84'/0'/0'/0/2",
+            "m/84'/0'/0'/1/0", "m/84'/0'/0'/1/1", "m/84'/0'/0'/1/2",
+        ]);
+        assert_eq!(paths("m/44'/60'/0'/0/[0-999]").len(), 1000);
+        assert_eq!(paths("m/0/[7-7]h"), ["m/0/7'"]);
+        assert_eq!(paths("M/<0h;1'>"), ["M/0'", "M/1'"]);
+        assert_eq!(paths("0/1"), ["0/1"]);
+        assert_eq!(paths("m"), ["m"]);
+
+        let template: PathTemplate = "m/84'/0'/0'/<0;1>/*".parse().unwrap();
+        let first: Vec<String> = template.paths().take(2).map(|path| path.to_string()).collect();
+
+        assert_eq!(first, ["m/84'/0'/0'/0/0", "m/84'/0'/0'/0/1"]);
+        assert_eq!(template.segments()[4], Segment::Wildcard { hardened: false });
+        assert_eq!(Segment::Wildcard { hardened: true }.get(MAX_INDEX), ChildNumber::hardened_from_u32(MAX_INDEX));
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for template in ["m/84'/0'/0'/<0;1>/*", "m/44'/60'/0'/0/[0-999]", "M/[1-5]'/*'/<0';1>", "0/*", ""].iter() {
+            let parsed: PathTemplate = template.parse().unwrap();
+
+            assert_eq!(&parsed.to_string(), template);
+            assert_eq!(format!("{:#}", parsed).parse(), Ok(parsed));
+        }
+
+        let path: DerivationPath = "m/44'/60'/0'/0/0".parse().unwrap();
+
+        assert_eq!(PathTemplate::from(path.clone()).paths().collect::<Vec<_>>(), [path]);
+    }
+
+    #[test]
+    fn invalid_templates() {
+        use PathErrorKind::*;
+
+        let vectors = [
+            ("m/<>", 1, "<>", EmptySegment),
+            ("m/<0;>", 1, "<0;>", EmptySegment),
+            ("m/<0;1", 1, "<0;1", InvalidTemplate),
+            ("m/<0;1>'", 1, "<0;1>'", InvalidTemplate),
+            ("m/[0-1", 1, "[0-1", InvalidTemplate),
+            ("m/[5-1]", 1, "[5-1]", InvalidTemplate),
+            ("m/[0'-1]", 1, "[0'-1]", InvalidTemplate),
+            ("m/[1]", 1, "[1]", InvalidTemplate),
+            ("m/[0-2147483648]", 1, "[0-2147483648]", Overflow),
+            ("m/[0-1]x", 1, "[0-1]x", BadHardenedMarker),
+            ("m/0/**", 2, "**", BadHardenedMarker),
+            ("m//*", 1, "", EmptySegment),
+        ];
+
+        for (template, segment, text, kind) in vectors.iter() {
+            let expected = Error::InvalidPath(PathError::new(*segment, text, *kind));
+
+            assert_eq!(template.parse::<PathTemplate>(), Err(expected), "{:?} should be rejected", template);
+        }
+    }
+
+    #[test]
+    fn derive_batch() {
+        let seed = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+        let template: PathTemplate = "m/0'/<1;2>/[0-3]".parse().unwrap();
+        let keys = ExtendedPrivKey::derive_batch(seed, &template).unwrap();
+        let mut count = 0;
+
+        for (key, path) in keys.zip(template.paths()) {
+            let (derived_path, key) = key.unwrap();
+
+            assert_eq!(derived_path, path);
+            assert_eq!(key, ExtendedPrivKey::derive(seed, path).unwrap());
+            count += 1;
+        }
+
+        assert_eq!(count, 8);
+
+        let public: PathTemplate = "M/0/[0-3]".parse().unwrap();
+        assert!(matches!(ExtendedPrivKey::derive_batch(seed, &public), Err(Error::PublicPathRoot)));
+    }
+}
@@ -0,0 +1,187 @@
+//! A cache of intermediate keys for deriving many paths from one seed.
+
+use std::collections::HashMap;
+
+use crate::bip32::GenericExtendedPrivKey;
+use crate::bip44::{ChildNumber, IntoDerivationPath};
+use crate::curve::{Curve, Secp256k1};
+use crate::Error;
+
+/// Number of intermediate keys kept by `GenericKeyTree::new`.
+pub const DEFAULT_CAPACITY: usize = 256;
+
+struct Entry<C: Curve> {
+    key: GenericExtendedPrivKey<C>,
+    last_used: u64,
+}
+
+/// Derives keys from a master key, memoising the intermediate keys along
+/// each path so that sibling paths only derive the levels they don't share.
+///
+/// Only strict prefixes of requested paths are cached, the requested keys
+/// themselves are returned without being stored. Once `capacity` keys are
+/// cached the least recently used one is evicted. Keys wipe their secrets
+/// when dropped, so evicted keys and the whole tree leave nothing behind.
+pub struct GenericKeyTree<C: Curve> {
+    master: GenericExtendedPrivKey<C>,
+    cache: HashMap<Vec<ChildNumber>, Entry<C>>,
+    capacity: usize,
+    clock: u64,
+}
+
+pub type KeyTree = GenericKeyTree<Secp256k1>;
+
+impl<C: Curve> GenericKeyTree<C> {
+    pub fn new(seed: &[u8]) -> Result<GenericKeyTree<C>, Error> {
+        GenericKeyTree::with_capacity(seed, DEFAULT_CAPACITY)
+    }
+
+    /// Creates a tree caching at most `capacity` intermediate keys.
+    pub fn with_capacity(seed: &[u8], capacity: usize) -> Result<GenericKeyTree<C>, Error> {
+        let master = GenericExtendedPrivKey::derive(seed, "m")?;
+
+        Ok(GenericKeyTree {
+            master,
+            cache: HashMap::new(),
+            capacity,
+            clock: 0,
+        })
+    }
+
+    /// Derives the key at `path`, the same as `GenericExtendedPrivKey::derive`
+    /// with the tree's seed. Relative paths are applied to the master key,
+    /// paths starting from `M` fail with `Error::PublicPathRoot`.
+    pub fn derive<Path>(&mut self, path: Path) -> Result<GenericExtendedPrivKey<C>, Error>
+    where
+        Path: IntoDerivationPath,
+    {
+        let path = path.into()?;
+        path.check_private()?;
+
+        let children = path.as_ref();
+
+        if children.is_empty() {
+            return Ok(self.master.clone());
+        }
+
+        // Find the deepest cached ancestor, falling back to the master key.
+        let mut depth = children.len() - 1;
+        self.clock += 1;
+
+        let mut key = loop {
+            if depth == 0 {
+                break self.master.clone();
+            }
+
+            if let Some(entry) = self.cache.get_mut(&children[..depth]) {
+                entry.last_used = self.clock;
+                break entry.key.clone();
+            }
+
+            depth -= 1;
+        };
+
+        for (i, child) in children.iter().enumerate().skip(depth) {
+            key = key.child(*child)?;
+
+            if i + 1 < children.len() {
+                self.insert(children[..=i].to_vec(), key.clone());
+            }
+        }
+
+        Ok(key)
+    }
+
+    /// Number of cached intermediate keys.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Drops every cached key. The master key is kept.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    fn insert(&mut self, prefix: Vec<ChildNumber>, key: GenericExtendedPrivKey<C>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.cache.len() >= self.capacity {
+            self.evict();
+        }
+
+        self.cache.insert(prefix, Entry { key, last_used: self.clock });
+    }
+
+    fn evict(&mut self) {
+        let oldest = self.cache.iter().min_by_key(|(_, entry)| entry.last_used).map(|(prefix, _)| prefix.clone());
+
+        if let Some(prefix) = oldest {
+            self.cache.remove(&prefix);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip32::ExtendedPrivKey;
+
+    const SEED: &[u8] = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+
+    #[test]
+    fn matches_derive() {
+        let mut tree = KeyTree::new(SEED).unwrap();
+
+        for path in ["m", "m/0'", "m/0'/1", "m/0'/1/2'", "m/0'/1/2'/2", "m/0'/1/2'/2/1000000000", "m/0'/1/3", "0'/1"].iter() {
+            assert_eq!(tree.derive(*path).unwrap(), ExtendedPrivKey::derive(SEED, *path).unwrap(), "{} is invalid", path);
+        }
+    }
+
+    #[test]
+    fn caches_prefixes() {
+        let mut tree = KeyTree::new(SEED).unwrap();
+
+        for i in 0..20 {
+            tree.derive(format!("m/44'/60'/0'/0/{}", i).as_str()).unwrap();
+        }
+
+        // m/44', m/44'/60', m/44'/60'/0' and m/44'/60'/0'/0
+        assert_eq!(tree.len(), 4);
+
+        tree.derive("m/44'/60'/0'/1/0").unwrap();
+        assert_eq!(tree.len(), 5);
+
+        tree.clear();
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut tree = KeyTree::with_capacity(SEED, 2).unwrap();
+
+        tree.derive("m/1/2/3").unwrap();
+        assert_eq!(tree.len(), 2);
+
+        tree.derive("m/1/2/4").unwrap();
+        tree.derive("m/5/6").unwrap();
+
+        // m/1 was used least recently and made room for m/5.
+        assert_eq!(tree.len(), 2);
+        assert!(tree.cache.contains_key(&[ChildNumber::from(1), ChildNumber::from(2)][..]));
+        assert!(tree.cache.contains_key(&[ChildNumber::from(5)][..]));
+
+        assert_eq!(tree.derive("m/1/2/5").unwrap(), ExtendedPrivKey::derive(SEED, "m/1/2/5").unwrap());
+        assert_eq!(tree.derive("M/1/2/5"), Err(Error::PublicPathRoot));
+        assert_eq!(KeyTree::with_capacity(SEED, 0).unwrap().derive("m/1/2").unwrap(), ExtendedPrivKey::derive(SEED, "m/1/2").unwrap());
+    }
+}